@@ -6,10 +6,10 @@ extern crate serde_derive;
 use failure::Error;
 use structopt::StructOpt;
 
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::prelude::*;
 use std::io::BufWriter;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str;
 
 #[derive(Debug, StructOpt)]
@@ -20,6 +20,15 @@ struct Opts {
     /// Output file. Defaults to stdout
     #[structopt(parse(from_os_str))]
     output_file: Option<PathBuf>,
+
+    /// Read the problem page from a saved HTML file, instead of downloading it
+    #[structopt(long = "from-file", parse(from_os_str))]
+    from_file: Option<PathBuf>,
+
+    /// Directory with previously downloaded problem pages, named like 42.html. Pages that aren't
+    /// there yet are downloaded and saved to it.
+    #[structopt(long = "cache-dir", parse(from_os_str))]
+    cache_dir: Option<PathBuf>,
 }
 
 fn main() {
@@ -28,8 +37,12 @@ fn main() {
 
 fn run() -> Result<(), Error> {
     let opts = Opts::from_args();
-    let html = fetch_problem_html(opts.problem)?;
-    let problem = parse_problem(opts.problem, &html)?;
+    let problem = load_problem(
+        opts.problem,
+        opts.from_file.as_ref().map(PathBuf::as_path),
+        opts.cache_dir.as_ref().map(PathBuf::as_path),
+        fetch_problem_html,
+    )?;
     let output = render_problem(&problem)?;
 
     if let Some(path) = opts.output_file {
@@ -85,10 +98,10 @@ fn parse_problem(number: u32, html: &str) -> Result<Problem, Error> {
                         return Some(t.to_string());
                     }
 
+                    // The text inside other elements (<b>, <span>, ...) shows up as descendants too
                     let el = ElementRef::wrap(node)?;
                     match el.value().name() {
                         "br" => Some("\n".to_string()),
-                        "b" => Some(el.text().collect::<String>()),
                         _ => None,
                     }
                 })
@@ -107,6 +120,37 @@ fn parse_problem(number: u32, html: &str) -> Result<Problem, Error> {
     })
 }
 
+fn load_problem(
+    number: u32,
+    from_file: Option<&Path>,
+    cache_dir: Option<&Path>,
+    fetch: impl FnOnce(u32) -> Result<String, Error>,
+) -> Result<Problem, Error> {
+    if let Some(path) = from_file {
+        return parse_problem(number, &fs::read_to_string(path)?);
+    }
+
+    let cached_path = cache_dir.map(|dir| dir.join(format!("{}.html", number)));
+
+    if let Some(ref path) = cached_path {
+        if path.is_file() {
+            return parse_problem(number, &fs::read_to_string(path)?);
+        }
+    }
+
+    let html = fetch(number)?;
+
+    // Only pages that parse get cached, so error pages are downloaded again next time
+    let problem = parse_problem(number, &html)?;
+
+    if let (Some(dir), Some(path)) = (cache_dir, cached_path) {
+        fs::create_dir_all(dir)?;
+        fs::write(path, &html)?;
+    }
+
+    Ok(problem)
+}
+
 fn fetch_problem_html(number: u32) -> Result<String, Error> {
     use reqwest::{Client, StatusCode};
 
@@ -121,3 +165,108 @@ fn fetch_problem_html(number: u32) -> Result<String, Error> {
         status => Err(format_err!("Unexpected response status: {:?}", status)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{load_problem, parse_problem, render_problem};
+    use std::env;
+    use std::fs;
+
+    const PROBLEM_1: &str = include_str!("../fixtures/problem_1.html");
+    const PROBLEM_17: &str = include_str!("../fixtures/problem_17.html");
+    const PROBLEM_18: &str = include_str!("../fixtures/problem_18.html");
+
+    #[test]
+    fn test_parse_problem() {
+        let problem = parse_problem(1, PROBLEM_1).unwrap();
+
+        assert_eq!(problem.number, 1);
+        assert_eq!(problem.title, "Multiples of 3 and 5");
+        assert_eq!(
+            problem.description_pars,
+            vec![
+                "If we list all the natural numbers below 10 that are multiples of 3 or 5, we \
+                 get 3, 5, 6 and 9. The sum of these multiples is 23.",
+                "Find the sum of all the multiples of 3 or 5 below 1000.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_line_breaks() {
+        let problem = parse_problem(18, PROBLEM_18).unwrap();
+
+        assert_eq!(problem.title, "Maximum path sum I");
+        assert_eq!(
+            &problem.description_pars[1..5],
+            &["3", "7 4", "2 4 6", "8 5 9 3"]
+        );
+    }
+
+    #[test]
+    fn test_parse_inline_markup() {
+        let problem = parse_problem(17, PROBLEM_17).unwrap();
+
+        assert_eq!(problem.description_pars.len(), 3);
+        assert_eq!(
+            problem.description_pars[2],
+            "NOTE: Do not count spaces or hyphens. For example, 342 (three hundred and forty-two) \
+             contains 23 letters and 115 (one hundred and fifteen) contains 20 letters. The use of \
+             \"and\" when writing out numbers is in compliance with British usage."
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse_problem(1, "<html><body><p>Not found</p></body></html>").unwrap_err();
+        assert_eq!(error.to_string(), "Problem info not found");
+
+        let untitled = PROBLEM_1.replace("<h2>Multiples of 3 and 5</h2>", "");
+        let error = parse_problem(1, &untitled).unwrap_err();
+        assert_eq!(error.to_string(), "Problem title not found");
+    }
+
+    #[test]
+    fn test_render_problem() {
+        let problem = parse_problem(1, PROBLEM_1).unwrap();
+        let output = render_problem(&problem).unwrap();
+
+        assert!(output.starts_with("/* Problem 1: Multiples of 3 and 5\n"));
+        assert!(output.contains(" * Find the sum of all the multiples of 3 or 5 below 1000.\n"));
+        assert!(output.contains("fn main() {"));
+    }
+
+    #[test]
+    fn test_problem_cache() {
+        let cache_dir = env::temp_dir().join(format!("new-problem-cache-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&cache_dir);
+
+        let fetched = load_problem(1, None, Some(cache_dir.as_path()), |_| {
+            Ok(PROBLEM_1.to_owned())
+        });
+        assert_eq!(fetched.unwrap().title, "Multiples of 3 and 5");
+        assert_eq!(
+            fs::read_to_string(cache_dir.join("1.html")).unwrap(),
+            PROBLEM_1
+        );
+
+        // Now the page comes from the cache, without downloading it again
+        let cached = load_problem(1, None, Some(cache_dir.as_path()), |_| {
+            panic!("Downloaded again")
+        });
+        assert_eq!(cached.unwrap().title, "Multiples of 3 and 5");
+
+        let from_file = cache_dir.join("1.html");
+        let saved = load_problem(1, Some(from_file.as_path()), None, |_| panic!("Downloaded"));
+        assert_eq!(saved.unwrap().title, "Multiples of 3 and 5");
+
+        // Pages that don't parse aren't cached
+        let error_page = load_problem(2, None, Some(cache_dir.as_path()), |_| {
+            Ok("<html><body><p>Rate limited</p></body></html>".to_owned())
+        });
+        assert!(error_page.is_err());
+        assert!(!cache_dir.join("2.html").exists());
+
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
+}
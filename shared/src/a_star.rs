@@ -1,21 +1,360 @@
+use num::traits::Zero;
+use std::error;
+use std::fmt;
 use std::hash::Hash;
+use std::ops::Add;
+use std::time::Duration;
 
 mod algorithm;
+mod bidirectional;
+mod ida_star;
 pub mod matrix;
 
 use self::algorithm::Algorithm;
-pub use self::matrix::{Coord, Matrix};
+pub use self::bidirectional::{bidirectional, Reversible};
+pub use self::ida_star::ida_star;
+pub use self::matrix::{Cells, Coord, Corner, Grid, Matrix, Movement};
 
 pub trait Traversable {
     type Coord: Copy + Eq + Hash;
+    type Cost: Clone + Ord + Add<Output = Self::Cost> + Zero;
 
-    fn heuristic(&self, start: Self::Coord) -> u32;
-    fn dist_between(&self, start: Self::Coord, end: Self::Coord) -> u32;
+    fn heuristic(&self, start: Self::Coord) -> Self::Cost;
+    fn dist_between(&self, start: Self::Coord, end: Self::Coord) -> Self::Cost;
     fn neighbors(&self, node: Self::Coord) -> Vec<Self::Coord>;
     fn reached_goal(&self, node: Self::Coord) -> bool;
 }
 
-pub fn a_star<T: Traversable>(graph: &T, start: T::Coord) -> Vec<T::Coord> {
-    let astar = Algorithm::new(graph, start);
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    // The search was given no start nodes
+    NoStart,
+    // Every node reachable from the start was expanded without reaching the goal
+    Unreachable,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NoStart => write!(f, "No start nodes were given"),
+            Error::Unreachable => write!(f, "There's no path from start to goal"),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+// The cost of the path found and its nodes, from start to goal
+pub type SearchResult<C, K> = Result<(K, Vec<C>), Error>;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Statistics {
+    // Nodes taken out of the open set and had their neighbors explored
+    pub expanded: usize,
+    pub max_open_set: usize,
+    pub elapsed: Duration,
+}
+
+// How to order open nodes that have the same f-score
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TieBreaking {
+    // Prefer the nodes the heuristic considers closest to the goal, i.e. the ones with the highest
+    // g-score. Usually expands the fewest nodes.
+    #[default]
+    LowestHeuristic,
+    // Prefer the nodes with the lowest g-score
+    LowestCost,
+    // Prefer the nodes that were discovered first
+    InsertionOrder,
+}
+
+// The cheapest path from `start` to a node where `reached_goal` holds, along with its cost (which
+// doesn't include the start node itself)
+pub fn a_star<T: Traversable>(graph: &T, start: T::Coord) -> SearchResult<T::Coord, T::Cost> {
+    a_star_with_statistics(graph, start).0
+}
+
+pub fn a_star_with_statistics<T: Traversable>(
+    graph: &T,
+    start: T::Coord,
+) -> (SearchResult<T::Coord, T::Cost>, Statistics) {
+    a_star_from_with_statistics(graph, Some(start))
+}
+
+// Like `a_star`, but the path may begin at any of `starts`
+pub fn a_star_from<T, I>(graph: &T, starts: I) -> SearchResult<T::Coord, T::Cost>
+where
+    T: Traversable,
+    I: IntoIterator<Item = T::Coord>,
+{
+    a_star_from_with_statistics(graph, starts).0
+}
+
+pub fn a_star_from_with_statistics<T, I>(
+    graph: &T,
+    starts: I,
+) -> (SearchResult<T::Coord, T::Cost>, Statistics)
+where
+    T: Traversable,
+    I: IntoIterator<Item = T::Coord>,
+{
+    a_star_with_tie_breaking(graph, starts, TieBreaking::default())
+}
+
+pub fn a_star_with_tie_breaking<T, I>(
+    graph: &T,
+    starts: I,
+    tie_breaking: TieBreaking,
+) -> (SearchResult<T::Coord, T::Cost>, Statistics)
+where
+    T: Traversable,
+    I: IntoIterator<Item = T::Coord>,
+{
+    let astar = Algorithm::new(
+        graph,
+        starts,
+        |node| graph.reached_goal(node),
+        |node| graph.heuristic(node),
+        tie_breaking,
+    );
+
     astar.perform()
 }
+
+// Uniform-cost search: like `a_star`, but ignoring the graph's heuristic, starting from any of
+// `starts` and stopping at the first node accepted by `goal`. Useful when there's no good heuristic
+// available, or to check one.
+pub fn dijkstra<T, I, G>(graph: &T, starts: I, goal: G) -> SearchResult<T::Coord, T::Cost>
+where
+    T: Traversable,
+    I: IntoIterator<Item = T::Coord>,
+    G: Fn(T::Coord) -> bool,
+{
+    dijkstra_with_statistics(graph, starts, goal).0
+}
+
+pub fn dijkstra_with_statistics<T, I, G>(
+    graph: &T,
+    starts: I,
+    goal: G,
+) -> (SearchResult<T::Coord, T::Cost>, Statistics)
+where
+    T: Traversable,
+    I: IntoIterator<Item = T::Coord>,
+    G: Fn(T::Coord) -> bool,
+{
+    let dijkstra = Algorithm::new(
+        graph,
+        starts,
+        goal,
+        |_| T::Cost::zero(),
+        TieBreaking::InsertionOrder,
+    );
+    dijkstra.perform()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{a_star, a_star_with_statistics, dijkstra, dijkstra_with_statistics};
+    use super::{a_star_with_tie_breaking, ida_star, Cells, Corner, Error, Grid};
+    use super::{TieBreaking, Traversable};
+    use num::rational::Ratio;
+
+    // A line of nodes 0..=9, where stepping into node n costs n, plus a costly shortcut from 0 to 9
+    struct Line;
+
+    impl Traversable for Line {
+        type Coord = u32;
+        type Cost = u32;
+
+        fn heuristic(&self, _start: u32) -> u32 {
+            0
+        }
+
+        fn dist_between(&self, start: u32, end: u32) -> u32 {
+            if start == 0 && end == 9 {
+                100
+            } else {
+                end
+            }
+        }
+
+        fn neighbors(&self, node: u32) -> Vec<u32> {
+            let mut neighbors = vec![];
+
+            if node > 0 {
+                neighbors.push(node - 1);
+            }
+
+            if node < 9 {
+                neighbors.push(node + 1);
+            }
+
+            if node == 0 {
+                neighbors.push(9);
+            }
+
+            neighbors
+        }
+
+        fn reached_goal(&self, node: u32) -> bool {
+            node == 9
+        }
+    }
+
+    // Nodes 1..=8, where going from n to m costs m/n, either by doubling or by adding one
+    struct Fractions;
+
+    impl Traversable for Fractions {
+        type Coord = u64;
+        type Cost = Ratio<u64>;
+
+        fn heuristic(&self, _start: u64) -> Ratio<u64> {
+            Ratio::from_integer(0)
+        }
+
+        fn dist_between(&self, start: u64, end: u64) -> Ratio<u64> {
+            Ratio::new(end, start)
+        }
+
+        fn neighbors(&self, node: u64) -> Vec<u64> {
+            vec![node + 1, node * 2]
+                .into_iter()
+                .filter(|&next| next <= 8)
+                .collect()
+        }
+
+        fn reached_goal(&self, node: u64) -> bool {
+            node == 6
+        }
+    }
+
+    // S → A → C → G is the cheapest path, but A's heuristic, while admissible, is inconsistent:
+    // C gets expanded first through B, and then needs to be reopened when reached through A.
+    struct Inconsistent;
+
+    impl Traversable for Inconsistent {
+        type Coord = char;
+        type Cost = u32;
+
+        fn heuristic(&self, node: char) -> u32 {
+            if node == 'A' {
+                5
+            } else {
+                0
+            }
+        }
+
+        fn dist_between(&self, start: char, end: char) -> u32 {
+            match (start, end) {
+                ('S', 'A') | ('A', 'C') | ('B', 'C') => 1,
+                ('S', 'B') => 3,
+                ('C', 'G') => 5,
+                _ => unreachable!(),
+            }
+        }
+
+        fn neighbors(&self, node: char) -> Vec<char> {
+            match node {
+                'S' => vec!['A', 'B'],
+                'A' | 'B' => vec!['C'],
+                'C' => vec!['G'],
+                _ => vec![],
+            }
+        }
+
+        fn reached_goal(&self, node: char) -> bool {
+            node == 'G'
+        }
+    }
+
+    #[test]
+    fn test_a_star_cost() {
+        assert_eq!(a_star(&Line, 0), Ok((45, (0..=9).collect())));
+        assert_eq!(a_star(&Line, 9), Ok((0, vec![9])));
+    }
+
+    #[test]
+    fn test_inconsistent_heuristic() {
+        let expected = Ok((7, vec!['S', 'A', 'C', 'G']));
+
+        for &tie_breaking in &[
+            TieBreaking::LowestHeuristic,
+            TieBreaking::LowestCost,
+            TieBreaking::InsertionOrder,
+        ] {
+            let (result, statistics) =
+                a_star_with_tie_breaking(&Inconsistent, vec!['S'], tie_breaking);
+
+            assert_eq!(result, expected);
+            // C is expanded twice
+            assert_eq!(statistics.expanded, 6);
+        }
+    }
+
+    #[test]
+    fn test_tie_breaking() {
+        // Every cell along any right/down path has the same f-score
+        let grid = Grid::new(vec![vec![1; 10]; 10]);
+        let expanded = |tie_breaking| {
+            let (result, statistics) = a_star_with_tie_breaking(
+                &grid,
+                grid.cells(Cells::Corner(Corner::TopLeft)),
+                tie_breaking,
+            );
+            assert_eq!(result.map(|(cost, _)| cost), Ok(18));
+
+            statistics.expanded
+        };
+
+        assert_eq!(expanded(TieBreaking::LowestHeuristic), 19);
+        assert!(expanded(TieBreaking::LowestCost) > 50);
+    }
+
+    #[test]
+    fn test_ida_star() {
+        assert_eq!(ida_star(&Line, 0), a_star(&Line, 0));
+        assert_eq!(ida_star(&Fractions, 1), a_star(&Fractions, 1));
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        assert_eq!(dijkstra(&Line, vec![0], |node| node == 9), a_star(&Line, 0));
+        assert_eq!(
+            dijkstra(&Line, vec![2, 6], |node| node == 8),
+            Ok((15, vec![6, 7, 8]))
+        );
+        assert_eq!(
+            dijkstra(&Line, vec![5], |node| node < 3),
+            Ok((9, vec![5, 4, 3, 2]))
+        );
+    }
+
+    #[test]
+    fn test_fractional_costs() {
+        // 2/1 + 3/2 + 6/3 is cheaper than 2/1 + 4/2 + 5/4 + 6/5
+        let expected = Ratio::new(11, 2);
+        assert_eq!(a_star(&Fractions, 1), Ok((expected, vec![1, 2, 3, 6])));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(dijkstra(&Line, vec![], |_| true), Err(Error::NoStart));
+        assert_eq!(
+            dijkstra(&Line, vec![3], |node| node > 9),
+            Err(Error::Unreachable)
+        );
+    }
+
+    #[test]
+    fn test_statistics() {
+        let (_, statistics) = a_star_with_statistics(&Line, 9);
+        assert_eq!((statistics.expanded, statistics.max_open_set), (1, 1));
+
+        // Every node is expanded before learning the goal can't be reached
+        let (result, statistics) = dijkstra_with_statistics(&Line, vec![3], |node| node > 9);
+        assert_eq!(result, Err(Error::Unreachable));
+        assert_eq!(statistics.expanded, 10);
+        assert_eq!(statistics.max_open_set, 2);
+    }
+}
@@ -1,87 +1,138 @@
-use super::Traversable;
+use super::{Error, SearchResult, Statistics, TieBreaking, Traversable};
 use hashbrown::{HashMap, HashSet};
+use num::traits::Zero;
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
+use std::time::Instant;
 
-pub(super) struct Algorithm<'t, T: Traversable> {
+pub(super) struct Algorithm<'t, T: Traversable, G, H> {
     graph: &'t T,
+    goal: G,
+    heuristic: H,
+    tie_breaking: TieBreaking,
     closed_set: HashSet<T::Coord>,
     open_set: HashSet<T::Coord>,
-    open_set_queue: BinaryHeap<ScoredCoord<T::Coord>>,
+    open_set_queue: BinaryHeap<OpenEntry<T::Coord, T::Cost>>,
     came_from: HashMap<T::Coord, T::Coord>,
-    g_score: HashMap<T::Coord, u32>,
+    g_score: HashMap<T::Coord, T::Cost>,
+    pushed: usize,
+    statistics: Statistics,
 }
 
-impl<'t, T: Traversable> Algorithm<'t, T> {
-    pub(super) fn new(graph: &'t T, start: T::Coord) -> Self {
-        let mut open_set = HashSet::new();
-        open_set.insert(start);
-
-        let mut open_set_queue = BinaryHeap::new();
-        open_set_queue.push(scored(start, graph.heuristic(start)));
-
-        let mut g_score = HashMap::new();
-        g_score.insert(start, 0);
-
-        Self {
+impl<'t, T, G, H> Algorithm<'t, T, G, H>
+where
+    T: Traversable,
+    G: Fn(T::Coord) -> bool,
+    H: Fn(T::Coord) -> T::Cost,
+{
+    pub(super) fn new(
+        graph: &'t T,
+        starts: impl IntoIterator<Item = T::Coord>,
+        goal: G,
+        heuristic: H,
+        tie_breaking: TieBreaking,
+    ) -> Self {
+        let mut algorithm = Self {
             graph,
-            open_set,
-            open_set_queue,
+            goal,
+            heuristic,
+            tie_breaking,
+            open_set: HashSet::new(),
+            open_set_queue: BinaryHeap::new(),
             closed_set: HashSet::new(),
             came_from: HashMap::new(),
-            g_score,
+            g_score: HashMap::new(),
+            pushed: 0,
+            statistics: Statistics::default(),
+        };
+
+        for start in starts {
+            if !algorithm.g_score.contains_key(&start) {
+                algorithm.discover(start, T::Cost::zero());
+            }
         }
+
+        algorithm
     }
 
-    pub(super) fn perform(mut self) -> Vec<T::Coord> {
+    pub(super) fn perform(mut self) -> (SearchResult<T::Coord, T::Cost>, Statistics) {
+        let started_at = Instant::now();
+        let result = self.search();
+
+        self.statistics.elapsed = started_at.elapsed();
+        (result, self.statistics)
+    }
+
+    fn search(&mut self) -> SearchResult<T::Coord, T::Cost> {
+        if self.open_set.is_empty() {
+            return Err(Error::NoStart);
+        }
+
         while let Some(current) = self.visit() {
-            if self.graph.reached_goal(current) {
-                return self.reconstruct_path(current);
+            if (self.goal)(current) {
+                let cost = self.g_score[&current].clone();
+                return Ok((cost, self.reconstruct_path(current)));
             }
 
-            let current_g_score = self.g_score(current);
+            let current_g_score = self.g_score[&current].clone();
 
             for neighbor in self.graph.neighbors(current) {
-                if self.closed_set.contains(&neighbor) {
-                    continue;
-                }
-
                 let distance = self.graph.dist_between(current, neighbor);
-                let tentative_gscore = current_g_score.saturating_add(distance);
+                let tentative_gscore = current_g_score.clone() + distance;
 
-                let discovered = self.discover(neighbor, tentative_gscore);
+                let improved = self
+                    .g_score
+                    .get(&neighbor)
+                    .is_none_or(|g_score| tentative_gscore < *g_score);
 
-                if discovered || tentative_gscore < self.g_score(neighbor) {
+                // With an inconsistent heuristic a cheaper path may show up for a node that was
+                // already closed, so it has to be reopened
+                if improved {
+                    self.closed_set.remove(&neighbor);
                     self.came_from.insert(neighbor, current);
-                    self.g_score.insert(neighbor, tentative_gscore);
+                    self.discover(neighbor, tentative_gscore);
                 }
             }
         }
 
-        panic!("There's no path from start to goal");
+        Err(Error::Unreachable)
     }
 
+    // Pops the open node with the lowest f-score. Instead of updating entries in place when a node
+    // gets a better g-score, a new entry is pushed, and the outdated ones are skipped here.
     fn visit(&mut self) -> Option<T::Coord> {
-        let ScoredCoord { coord, .. } = self.open_set_queue.pop()?;
+        loop {
+            let OpenEntry { coord, g_score, .. } = self.open_set_queue.pop()?;
 
-        self.open_set.remove(&coord);
-        self.closed_set.insert(coord);
+            if self.closed_set.contains(&coord) || g_score != self.g_score[&coord] {
+                continue;
+            }
 
-        Some(coord)
-    }
+            self.open_set.remove(&coord);
+            self.closed_set.insert(coord);
+            self.statistics.expanded += 1;
 
-    fn discover(&mut self, node: T::Coord, tentative_gscore: u32) -> bool {
-        if !self.open_set.insert(node) {
-            return false;
+            return Some(coord);
         }
-
-        let fscore = tentative_gscore.saturating_add(self.graph.heuristic(node));
-        self.open_set_queue.push(scored(node, fscore));
-        true
     }
 
-    fn g_score(&mut self, node: T::Coord) -> u32 {
-        *self.g_score.entry(node).or_insert(std::u32::MAX)
+    fn discover(&mut self, node: T::Coord, g_score: T::Cost) {
+        let h_score = (self.heuristic)(node);
+
+        self.open_set_queue.push(OpenEntry {
+            coord: node,
+            f_score: g_score.clone() + h_score,
+            g_score: g_score.clone(),
+            tie_breaking: self.tie_breaking,
+            order: self.pushed,
+        });
+
+        self.pushed += 1;
+        self.g_score.insert(node, g_score);
+        self.open_set.insert(node);
+
+        let open_set_size = self.open_set.len();
+        self.statistics.max_open_set = self.statistics.max_open_set.max(open_set_size);
     }
 
     fn reconstruct_path(&self, start: T::Coord) -> Vec<T::Coord> {
@@ -98,32 +149,72 @@ impl<'t, T: Traversable> Algorithm<'t, T> {
     }
 }
 
-struct ScoredCoord<C> {
+struct OpenEntry<C, K> {
     coord: C,
-    score: u32,
+    f_score: K,
+    g_score: K,
+    tie_breaking: TieBreaking,
+    // Entries pushed earlier win remaining ties, so searches are deterministic
+    order: usize,
+}
+
+impl<C, K: Ord> Ord for OpenEntry<C, K> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // inverted on purpose, since we want the coordinates with the lowest f-score first
+        let by_g_score = match self.tie_breaking {
+            TieBreaking::LowestHeuristic => self.g_score.cmp(&other.g_score),
+            TieBreaking::LowestCost => other.g_score.cmp(&self.g_score),
+            TieBreaking::InsertionOrder => Ordering::Equal,
+        };
+
+        other
+            .f_score
+            .cmp(&self.f_score)
+            .then(by_g_score)
+            .then(other.order.cmp(&self.order))
+    }
+}
+
+impl<C, K: Ord> PartialOrd for OpenEntry<C, K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<C, K: Ord> PartialEq for OpenEntry<C, K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<C, K: Ord> Eq for OpenEntry<C, K> {}
+
+pub(super) struct ScoredCoord<C, K> {
+    pub(super) coord: C,
+    pub(super) score: K,
 }
 
-impl<C> Ord for ScoredCoord<C> {
+impl<C, K: Ord> Ord for ScoredCoord<C, K> {
     fn cmp(&self, other: &Self) -> Ordering {
         // inverted on purpose, since we want the coordinates with the lowest f-score first
         other.score.cmp(&self.score)
     }
 }
 
-impl<C> PartialOrd for ScoredCoord<C> {
+impl<C, K: Ord> PartialOrd for ScoredCoord<C, K> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl<C> PartialEq for ScoredCoord<C> {
+impl<C, K: Ord> PartialEq for ScoredCoord<C, K> {
     fn eq(&self, other: &Self) -> bool {
         self.score == other.score
     }
 }
 
-impl<C> Eq for ScoredCoord<C> {}
+impl<C, K: Ord> Eq for ScoredCoord<C, K> {}
 
-fn scored<C>(coord: C, score: u32) -> ScoredCoord<C> {
+pub(super) fn scored<C, K>(coord: C, score: K) -> ScoredCoord<C, K> {
     ScoredCoord { coord, score }
 }
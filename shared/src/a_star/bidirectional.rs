@@ -0,0 +1,176 @@
+use super::algorithm::{scored, ScoredCoord};
+use super::{Error, SearchResult, Traversable};
+use hashbrown::{HashMap, HashSet};
+use num::traits::Zero;
+use std::collections::BinaryHeap;
+
+// A graph that can also be walked backwards, which bidirectional search needs
+pub trait Reversible: Traversable {
+    // The nodes that have `node` among their neighbors
+    fn predecessors(&self, node: Self::Coord) -> Vec<Self::Coord>;
+}
+
+// One of the two uniform-cost searches, either going forward from the starts or backwards from the
+// goals
+struct Frontier<C, K> {
+    queue: BinaryHeap<ScoredCoord<C, K>>,
+    distance: HashMap<C, K>,
+    came_from: HashMap<C, C>,
+    settled: HashSet<C>,
+}
+
+impl<C: Copy + Eq + std::hash::Hash, K: Clone + Ord + Zero> Frontier<C, K> {
+    fn new(nodes: impl IntoIterator<Item = C>) -> Self {
+        let mut frontier = Frontier {
+            queue: BinaryHeap::new(),
+            distance: HashMap::new(),
+            came_from: HashMap::new(),
+            settled: HashSet::new(),
+        };
+
+        for node in nodes {
+            frontier.queue.push(scored(node, K::zero()));
+            frontier.distance.insert(node, K::zero());
+        }
+
+        frontier
+    }
+
+    // Skips entries made stale by later improvements
+    fn peek(&mut self) -> Option<K> {
+        while let Some(top) = self.queue.peek() {
+            if self.settled.contains(&top.coord) {
+                self.queue.pop();
+            } else {
+                return Some(top.score.clone());
+            }
+        }
+
+        None
+    }
+
+    fn pop(&mut self) -> Option<(C, K)> {
+        self.peek()?;
+
+        let ScoredCoord { coord, score } = self.queue.pop()?;
+        self.settled.insert(coord);
+
+        Some((coord, score))
+    }
+
+    fn relax(&mut self, from: C, to: C, distance: K) -> bool {
+        if self
+            .distance
+            .get(&to)
+            .is_some_and(|known| *known <= distance)
+        {
+            return false;
+        }
+
+        self.distance.insert(to, distance.clone());
+        self.came_from.insert(to, from);
+        self.queue.push(scored(to, distance));
+
+        true
+    }
+
+    // The nodes from `node` back to where this search started
+    fn trace(&self, node: C) -> Vec<C> {
+        let mut path = vec![node];
+        let mut current = node;
+
+        while let Some(&previous) = self.came_from.get(&current) {
+            current = previous;
+            path.push(current);
+        }
+
+        path
+    }
+}
+
+// Uniform-cost search from both ends at once, from any of `starts` towards any of `goals`,
+// stopping once the two searches can't find anything cheaper than the best meeting point so far.
+// The graph's heuristic and `reached_goal` aren't used.
+pub fn bidirectional<T, S, G>(graph: &T, starts: S, goals: G) -> SearchResult<T::Coord, T::Cost>
+where
+    T: Reversible,
+    S: IntoIterator<Item = T::Coord>,
+    G: IntoIterator<Item = T::Coord>,
+{
+    let mut forward: Frontier<T::Coord, T::Cost> = Frontier::new(starts);
+    let mut backward: Frontier<T::Coord, T::Cost> = Frontier::new(goals);
+
+    if forward.distance.is_empty() {
+        return Err(Error::NoStart);
+    }
+
+    let mut best: Option<(T::Cost, T::Coord)> = forward
+        .distance
+        .keys()
+        .find(|node| backward.distance.contains_key(node))
+        .map(|&node| (T::Cost::zero(), node));
+
+    while let (Some(forward_top), Some(backward_top)) = (forward.peek(), backward.peek()) {
+        let bound = forward_top.clone() + backward_top.clone();
+
+        if best.as_ref().is_some_and(|(cost, _)| bound >= *cost) {
+            break;
+        }
+
+        let going_forward = forward_top <= backward_top;
+
+        let (current, distance) = if going_forward {
+            forward.pop().unwrap()
+        } else {
+            backward.pop().unwrap()
+        };
+
+        let next_nodes = if going_forward {
+            graph.neighbors(current)
+        } else {
+            graph.predecessors(current)
+        };
+
+        for next in next_nodes {
+            let (this, other) = if going_forward {
+                let step = graph.dist_between(current, next);
+                (
+                    forward.relax(current, next, distance.clone() + step),
+                    &backward,
+                )
+            } else {
+                let step = graph.dist_between(next, current);
+                (
+                    backward.relax(current, next, distance.clone() + step),
+                    &forward,
+                )
+            };
+
+            if !this {
+                continue;
+            }
+
+            if let Some(other_distance) = other.distance.get(&next) {
+                let this_distance = if going_forward {
+                    &forward.distance[&next]
+                } else {
+                    &backward.distance[&next]
+                };
+
+                let total = this_distance.clone() + other_distance.clone();
+
+                if best.as_ref().is_none_or(|(cost, _)| total < *cost) {
+                    best = Some((total, next));
+                }
+            }
+        }
+    }
+
+    let (cost, meeting) = best.ok_or(Error::Unreachable)?;
+
+    let mut path = forward.trace(meeting);
+    path.reverse();
+    path.extend(backward.trace(meeting).into_iter().skip(1));
+
+    Ok((cost, path))
+}
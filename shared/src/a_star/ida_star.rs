@@ -0,0 +1,72 @@
+use super::{Error, SearchResult, Traversable};
+use num::traits::Zero;
+
+enum Outcome<K> {
+    Found(K),
+    // The smallest f-score beyond the bound, if there was any
+    Exceeded(Option<K>),
+}
+
+// Iterative-deepening A*: repeated depth-first searches, each one pruning nodes whose f-score goes
+// past a bound, which then grows to the smallest f-score that was pruned. Only the current path is
+// kept in memory, at the cost of visiting nodes many times.
+//
+// Like A*, this only finds the cheapest path if the heuristic never overestimates.
+pub fn ida_star<T: Traversable>(graph: &T, start: T::Coord) -> SearchResult<T::Coord, T::Cost> {
+    let mut bound = graph.heuristic(start);
+    let mut path = vec![start];
+
+    loop {
+        match search(graph, &mut path, T::Cost::zero(), &bound) {
+            Outcome::Found(cost) => return Ok((cost, path)),
+            Outcome::Exceeded(Some(next_bound)) => bound = next_bound,
+            Outcome::Exceeded(None) => return Err(Error::Unreachable),
+        }
+    }
+}
+
+fn search<T: Traversable>(
+    graph: &T,
+    path: &mut Vec<T::Coord>,
+    g_score: T::Cost,
+    bound: &T::Cost,
+) -> Outcome<T::Cost> {
+    let node = *path.last().unwrap();
+    let f_score = g_score.clone() + graph.heuristic(node);
+
+    if f_score > *bound {
+        return Outcome::Exceeded(Some(f_score));
+    }
+
+    if graph.reached_goal(node) {
+        return Outcome::Found(g_score);
+    }
+
+    let mut next_bound: Option<T::Cost> = None;
+
+    for neighbor in graph.neighbors(node) {
+        // Cycles never lead to a cheaper path
+        if path.contains(&neighbor) {
+            continue;
+        }
+
+        let tentative_gscore = g_score.clone() + graph.dist_between(node, neighbor);
+        path.push(neighbor);
+
+        match search(graph, path, tentative_gscore, bound) {
+            Outcome::Found(cost) => return Outcome::Found(cost),
+
+            Outcome::Exceeded(Some(f_score)) => {
+                if next_bound.as_ref().is_none_or(|next| f_score < *next) {
+                    next_bound = Some(f_score);
+                }
+            }
+
+            Outcome::Exceeded(None) => {}
+        }
+
+        path.pop();
+    }
+
+    Outcome::Exceeded(next_bound)
+}
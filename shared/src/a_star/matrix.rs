@@ -1,4 +1,6 @@
-use super::{a_star, Traversable};
+use super::{a_star, a_star_from, Error, Reversible, Traversable};
+use crate::data_reader;
+use std::str::FromStr;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Coord {
@@ -10,16 +12,16 @@ pub trait Matrix: Sized {
     fn nodes(&self) -> &[Vec<u32>];
     fn neighbors(&self, node: Coord) -> Vec<Coord>;
 
-    fn minimal_path_sum(&self) -> u32 {
-        let path = a_star(self, self.top_left());
-        self.path_sum(path)
+    fn minimal_path_sum(&self) -> Result<u64, Error> {
+        let (_, path) = a_star(self, self.top_left())?;
+        Ok(self.path_sum(path))
     }
 
-    fn path_sum(&self, iter: impl IntoIterator<Item = Coord>) -> u32 {
+    fn path_sum(&self, iter: impl IntoIterator<Item = Coord>) -> u64 {
         let nodes = self.nodes();
 
         iter.into_iter()
-            .map(|coord| nodes[coord.y as usize][coord.x as usize])
+            .map(|coord| nodes[coord.y as usize][coord.x as usize] as u64)
             .sum()
     }
 
@@ -46,13 +48,13 @@ pub trait Matrix: Sized {
         coord.x < self.width() && coord.y < self.height()
     }
 
-    fn heuristic(&self, start: Coord) -> u32 {
+    fn heuristic(&self, start: Coord) -> u64 {
         let end = self.bottom_right();
 
         let (x1, y1) = (start.x as f32, start.y as f32);
         let (x2, y2) = (end.x as f32, end.y as f32);
 
-        ((x1 - x2).powi(2) + (y1 - y2).powi(2)).sqrt() as u32
+        ((x1 - x2).powi(2) + (y1 - y2).powi(2)).sqrt() as u64
     }
 
     fn reached_goal(&self, node: Coord) -> bool {
@@ -62,12 +64,14 @@ pub trait Matrix: Sized {
 
 impl<M: Matrix> Traversable for M {
     type Coord = Coord;
+    // Cells hold u32s, so path costs are summed as u64s to leave room for long paths
+    type Cost = u64;
 
     fn neighbors(&self, node: Coord) -> Vec<Coord> {
         <Self as Matrix>::neighbors(&self, node)
     }
 
-    fn heuristic(&self, start: Coord) -> u32 {
+    fn heuristic(&self, start: Coord) -> u64 {
         <Self as Matrix>::heuristic(&self, start)
     }
 
@@ -75,15 +79,469 @@ impl<M: Matrix> Traversable for M {
         <Self as Matrix>::reached_goal(&self, node)
     }
 
-    fn dist_between(&self, start: Coord, end: Coord) -> u32 {
-        let mut x = [start.x, end.x];
-        x.sort_unstable();
+    fn dist_between(&self, start: Coord, end: Coord) -> u64 {
+        assert!(start != end);
 
-        let mut y = [start.y, end.y];
-        y.sort_unstable();
+        self.nodes()[end.y as usize][end.x as usize] as u64
+    }
+}
+
+// The moves allowed from each cell of a `Grid`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Movement {
+    RightDown,
+    UpRightDown,
+    FourWay,
+    EightWay,
+    // (dx, dy) offsets, with y growing downwards
+    Custom(Vec<(i32, i32)>),
+}
+
+impl Movement {
+    fn offsets(&self) -> &[(i32, i32)] {
+        const RIGHT_DOWN: &[(i32, i32)] = &[(1, 0), (0, 1)];
+        const UP_RIGHT_DOWN: &[(i32, i32)] = &[(0, -1), (1, 0), (0, 1)];
+        const FOUR_WAY: &[(i32, i32)] = &[(0, -1), (-1, 0), (1, 0), (0, 1)];
+        const EIGHT_WAY: &[(i32, i32)] = &[
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ];
+
+        match self {
+            Movement::RightDown => RIGHT_DOWN,
+            Movement::UpRightDown => UP_RIGHT_DOWN,
+            Movement::FourWay => FOUR_WAY,
+            Movement::EightWay => EIGHT_WAY,
+            Movement::Custom(offsets) => offsets,
+        }
+    }
+
+    // A lower bound for the number of moves from `start` to `end`, if we know one
+    fn min_moves(&self, start: Coord, end: Coord) -> Option<u32> {
+        let dx = (start.x as i64 - end.x as i64).unsigned_abs() as u32;
+        let dy = (start.y as i64 - end.y as i64).unsigned_abs() as u32;
+
+        match self {
+            Movement::EightWay => Some(dx.max(dy)),
+            Movement::Custom(_) => None,
+            _ => Some(dx + dy),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+// A set of cells where paths through a `Grid` may start or end
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cells {
+    Column(u32),
+    Row(u32),
+    LastColumn,
+    LastRow,
+    Corner(Corner),
+}
+
+// A matrix of weights, where the cost of a path is the sum of the cells it goes through
+#[derive(Clone, Debug)]
+pub struct Grid {
+    nodes: Vec<Vec<u32>>,
+    movement: Movement,
+    starts: Cells,
+    goals: Cells,
+    min_value: u32,
+}
+
+impl Grid {
+    // By default paths go from the top left to the bottom right corner, moving in any of the four
+    // directions
+    pub fn new(nodes: Vec<Vec<u32>>) -> Grid {
+        assert!(!nodes.is_empty() && !nodes[0].is_empty());
+        assert!(nodes.iter().all(|row| row.len() == nodes[0].len()));
+
+        let min_value = nodes.iter().flatten().cloned().min().unwrap();
+
+        Grid {
+            nodes,
+            movement: Movement::FourWay,
+            starts: Cells::Corner(Corner::TopLeft),
+            goals: Cells::Corner(Corner::BottomRight),
+            min_value,
+        }
+    }
+
+    pub fn with_movement(mut self, movement: Movement) -> Grid {
+        self.movement = movement;
+        self
+    }
+
+    pub fn with_starts(mut self, starts: Cells) -> Grid {
+        self.starts = starts;
+        self
+    }
+
+    pub fn with_goals(mut self, goals: Cells) -> Grid {
+        self.goals = goals;
+        self
+    }
+
+    pub fn minimal_path(&self) -> Result<Vec<Coord>, Error> {
+        let (_, path) = a_star_from(self, self.cells(self.starts))?;
+        Ok(path)
+    }
+
+    // The cheapest path from the top left to the bottom right corner when only moving right and
+    // down, regardless of the grid's configured movement, starts and goals. Solved by dynamic
+    // programming in a single pass over the cells instead of searching.
+    pub fn minimal_monotone_path(&self) -> (u64, Vec<Coord>) {
+        self.monotone_path(u64::min)
+    }
+
+    pub fn maximal_monotone_path(&self) -> (u64, Vec<Coord>) {
+        self.monotone_path(u64::max)
+    }
+
+    fn monotone_path(&self, pick: impl Fn(u64, u64) -> u64) -> (u64, Vec<Coord>) {
+        let (width, height) = (self.width() as usize, self.height() as usize);
+        let mut totals = vec![vec![0; width]; height];
+
+        for y in 0..height {
+            for x in 0..width {
+                let value = self.nodes[y][x] as u64;
+
+                totals[y][x] = match (x, y) {
+                    (0, 0) => value,
+                    (0, _) => value + totals[y - 1][0],
+                    (_, 0) => value + totals[0][x - 1],
+                    _ => value + pick(totals[y - 1][x], totals[y][x - 1]),
+                };
+            }
+        }
+
+        let (mut x, mut y) = (width - 1, height - 1);
+        let mut path = vec![Coord {
+            x: x as u32,
+            y: y as u32,
+        }];
+
+        while (x, y) != (0, 0) {
+            let from_above =
+                y > 0 && (x == 0 || totals[y - 1][x] == pick(totals[y - 1][x], totals[y][x - 1]));
+
+            if from_above {
+                y -= 1;
+            } else {
+                x -= 1;
+            }
+
+            path.push(Coord {
+                x: x as u32,
+                y: y as u32,
+            });
+        }
+
+        path.reverse();
+        (totals[height - 1][width - 1], path)
+    }
+
+    pub fn cells(&self, cells: Cells) -> Vec<Coord> {
+        let (width, height) = (self.width(), self.height());
+
+        match self.resolve(cells) {
+            Cells::Column(x) => (0..height).map(|y| Coord { x, y }).collect(),
+            Cells::Row(y) => (0..width).map(|x| Coord { x, y }).collect(),
+            Cells::Corner(corner) => vec![self.corner(corner)],
+            Cells::LastColumn | Cells::LastRow => unreachable!(),
+        }
+    }
+
+    fn resolve(&self, cells: Cells) -> Cells {
+        match cells {
+            Cells::LastColumn => Cells::Column(self.width() - 1),
+            Cells::LastRow => Cells::Row(self.height() - 1),
+            other => other,
+        }
+    }
+
+    fn corner(&self, corner: Corner) -> Coord {
+        let (right, bottom) = (self.width() - 1, self.height() - 1);
+
+        match corner {
+            Corner::TopLeft => Coord { x: 0, y: 0 },
+            Corner::TopRight => Coord { x: right, y: 0 },
+            Corner::BottomLeft => Coord { x: 0, y: bottom },
+            Corner::BottomRight => Coord {
+                x: right,
+                y: bottom,
+            },
+        }
+    }
+
+    // The closest cell in the goal set to `node`, counting moves as the crow flies
+    fn closest_goal(&self, node: Coord) -> Coord {
+        match self.resolve(self.goals) {
+            Cells::Column(x) => Coord { x, y: node.y },
+            Cells::Row(y) => Coord { x: node.x, y },
+            Cells::Corner(corner) => self.corner(corner),
+            Cells::LastColumn | Cells::LastRow => unreachable!(),
+        }
+    }
+}
 
-        assert!(x[1] - x[0] == 1 || y[1] - y[0] == 1);
+impl Matrix for Grid {
+    fn nodes(&self) -> &[Vec<u32>] {
+        &self.nodes
+    }
+
+    fn neighbors(&self, node: Coord) -> Vec<Coord> {
+        self.movement
+            .offsets()
+            .iter()
+            .map(|&(dx, dy)| Coord {
+                x: (node.x as i64 + dx as i64) as u32,
+                y: (node.y as i64 + dy as i64) as u32,
+            })
+            .filter(|&neighbor| neighbor != node && self.contains(neighbor))
+            .collect()
+    }
+
+    fn minimal_path_sum(&self) -> Result<u64, Error> {
+        self.minimal_path().map(|path| self.path_sum(path))
+    }
+
+    // Every move costs at least as much as the cheapest cell
+    fn heuristic(&self, start: Coord) -> u64 {
+        let goal = self.closest_goal(start);
+
+        self.movement
+            .min_moves(start, goal)
+            .map_or(0, |moves| moves as u64 * self.min_value as u64)
+    }
+
+    fn reached_goal(&self, node: Coord) -> bool {
+        match self.resolve(self.goals) {
+            Cells::Column(x) => node.x == x,
+            Cells::Row(y) => node.y == y,
+            Cells::Corner(corner) => node == self.corner(corner),
+            Cells::LastColumn | Cells::LastRow => unreachable!(),
+        }
+    }
+}
+
+impl Reversible for Grid {
+    fn predecessors(&self, node: Coord) -> Vec<Coord> {
+        self.movement
+            .offsets()
+            .iter()
+            .map(|&(dx, dy)| Coord {
+                x: (node.x as i64 - dx as i64) as u32,
+                y: (node.y as i64 - dy as i64) as u32,
+            })
+            .filter(|&neighbor| neighbor != node && self.contains(neighbor))
+            .collect()
+    }
+}
+
+// Parses comma-separated rows, one per line, as in the matrix files under data/
+impl FromStr for Grid {
+    type Err = data_reader::Error;
+
+    fn from_str(text: &str) -> Result<Grid, data_reader::Error> {
+        Ok(Grid::new(data_reader::matrix(text)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cells, Coord, Corner, Grid, Matrix, Movement};
+    use crate::a_star::{self, Traversable};
+    use crate::data_reader::{Error, Position};
+    use crate::test_random::Lcg;
+
+    const EXAMPLE: &str = "131,673,234,103,18
+201,96,342,965,150
+630,803,746,422,111
+537,699,497,121,956
+805,732,524,37,331
+";
+
+    fn example() -> Grid {
+        EXAMPLE.parse().unwrap()
+    }
+
+    #[test]
+    fn test_movements() {
+        let two_ways = example().with_movement(Movement::RightDown);
+        assert_eq!(two_ways.minimal_path_sum(), Ok(2427));
+
+        let three_ways = example()
+            .with_movement(Movement::UpRightDown)
+            .with_starts(Cells::Column(0))
+            .with_goals(Cells::LastColumn);
+        assert_eq!(three_ways.minimal_path_sum(), Ok(994));
+
+        let four_ways = example().with_movement(Movement::FourWay);
+        assert_eq!(four_ways.minimal_path_sum(), Ok(2297));
+
+        let eight_ways = example().with_movement(Movement::EightWay);
+        assert_eq!(eight_ways.minimal_path_sum(), Ok(1277));
+
+        // Knight moves only reach the opposite corner of a 5x5 board in an even number of moves
+        let knight = example().with_movement(Movement::Custom(vec![
+            (1, 2),
+            (2, 1),
+            (-1, 2),
+            (2, -1),
+            (1, -2),
+            (-2, 1),
+            (-1, -2),
+            (-2, -1),
+        ]));
+        let path = knight.minimal_path().unwrap();
+        assert_eq!(path.first(), Some(&Coord { x: 0, y: 0 }));
+        assert_eq!(path.last(), Some(&Coord { x: 4, y: 4 }));
+        assert_eq!((path.len() - 1) % 2, 0);
+
+        // Moves may skip over cells entirely
+        let jumps = Grid::new(vec![vec![1, 9, 2], vec![9, 9, 9], vec![3, 9, 4]])
+            .with_movement(Movement::Custom(vec![(2, 0), (0, 2)]));
+        assert_eq!(jumps.minimal_path_sum(), Ok(1 + 2 + 4));
+    }
+
+    #[test]
+    fn test_start_and_goal_sets() {
+        let grid = example()
+            .with_starts(Cells::Row(0))
+            .with_goals(Cells::Corner(Corner::BottomLeft));
+        let path = grid.minimal_path().unwrap();
+
+        assert_eq!(path.first(), Some(&Coord { x: 0, y: 0 }));
+        assert_eq!(path.last(), Some(&Coord { x: 0, y: 4 }));
+        assert_eq!(grid.minimal_path_sum(), Ok(131 + 201 + 630 + 537 + 805));
+
+        let grid = example().with_goals(Cells::LastRow);
+        assert_eq!(grid.minimal_path_sum(), Ok(1966));
+    }
+
+    #[test]
+    fn test_costs_beyond_u32() {
+        let max = u32::MAX as u64;
+        let grid = Grid::new(vec![vec![u32::MAX; 3]; 3]);
+
+        assert_eq!(grid.minimal_path_sum(), Ok(5 * max));
+        assert_eq!(grid.minimal_monotone_path().0, 5 * max);
+        assert_eq!(a_star::a_star(&grid, grid.top_left()).unwrap().0, 4 * max);
+    }
+
+    fn generated(size: u32, seed: u64) -> Grid {
+        let mut random = Lcg::new(seed);
+
+        let nodes = (0..size)
+            .map(|_| (0..size).map(|_| random.below(1000)).collect())
+            .collect();
+
+        Grid::new(nodes)
+    }
+
+    #[test]
+    fn test_monotone_paths() {
+        let (total, path) = example().minimal_monotone_path();
+        assert_eq!(total, 2427);
+        assert_eq!(
+            path,
+            example()
+                .with_movement(Movement::RightDown)
+                .minimal_path()
+                .unwrap()
+        );
+
+        let (total, path) = example().maximal_monotone_path();
+        assert_eq!(total, example().path_sum(path.clone()));
+        assert_eq!(total, 4416);
+
+        for seed in 0..10 {
+            let grid = generated(15, seed).with_movement(Movement::RightDown);
+            let (total, path) = grid.minimal_monotone_path();
+
+            assert_eq!(Ok(total), grid.minimal_path_sum());
+            assert_eq!(total, grid.path_sum(path));
+        }
+    }
+
+    // Every search variant must agree on the cost of the cheapest path
+    fn assert_variants_agree(grid: &Grid) {
+        let starts = grid.cells(Cells::Corner(Corner::TopLeft));
+        let goals = grid.cells(Cells::Corner(Corner::BottomRight));
+        let start = starts[0];
+
+        let (cost, path) = a_star::a_star(grid, start).unwrap();
+        let path_cost = |path: &[Coord]| -> u64 {
+            path.windows(2)
+                .map(|step| grid.dist_between(step[0], step[1]))
+                .sum()
+        };
+
+        assert_eq!(path_cost(&path), cost);
+
+        let (dijkstra_cost, _) = a_star::dijkstra(grid, starts.clone(), |node| {
+            Matrix::reached_goal(grid, node)
+        })
+        .unwrap();
+        assert_eq!(dijkstra_cost, cost);
+
+        let (ida_cost, ida_path) = a_star::ida_star(grid, start).unwrap();
+        assert_eq!((ida_cost, path_cost(&ida_path)), (cost, cost));
+
+        let (bidirectional_cost, bidirectional_path) =
+            a_star::bidirectional(grid, starts, goals).unwrap();
+        assert_eq!(bidirectional_cost, cost);
+        assert_eq!(path_cost(&bidirectional_path), cost);
+        assert_eq!(bidirectional_path.first(), Some(&start));
+        assert_eq!(bidirectional_path.last(), Some(&grid.bottom_right()));
+    }
+
+    #[test]
+    fn test_search_variants() {
+        // The examples from problems 81 and 83
+        for movement in [Movement::RightDown, Movement::FourWay] {
+            assert_variants_agree(&example().with_movement(movement.clone()));
+
+            for seed in 0..5 {
+                assert_variants_agree(&generated(6, seed).with_movement(movement.clone()));
+            }
+        }
+
+        assert_variants_agree(&example().with_movement(Movement::EightWay));
+    }
 
-        self.nodes()[end.y as usize][end.x as usize]
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!("".parse::<Grid>().unwrap_err(), Error::Empty);
+        assert_eq!(
+            "1,2\n3,x".parse::<Grid>().unwrap_err(),
+            Error::InvalidNumber {
+                position: Position { line: 2, column: 3 },
+                value: "x".to_owned()
+            }
+        );
+        assert_eq!(
+            "1,2\n3".parse::<Grid>().unwrap_err(),
+            Error::WrongLength {
+                position: Position { line: 2, column: 1 },
+                expected: 2,
+                found: 1
+            }
+        );
     }
 }
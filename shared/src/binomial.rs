@@ -0,0 +1,222 @@
+use crate::modular::{mul_mod, pow_mod};
+use num::integer::gcd;
+use num::{BigUint, One};
+
+macro_rules! checked_impl(
+    ($name:ident, $ty:ident) => (
+        // Returns None if nCr doesn't fit in the result type. Intermediate values never exceed the
+        // result, so there are no spurious overflows.
+        pub fn $name(n: $ty, k: $ty) -> Option<$ty> {
+            if k > n {
+                return Some(0);
+            }
+
+            let k = k.min(n - k);
+            let mut result: $ty = 1;
+
+            for i in 1..=k {
+                // result * (n - k + i) is always divisible by i
+                let common = gcd(result, i);
+                let factor = (n - k + i) / (i / common);
+
+                result = (result / common).checked_mul(factor)?;
+            }
+
+            Some(result)
+        }
+    )
+);
+
+checked_impl!(checked_u64, u64);
+checked_impl!(checked_u128, u128);
+
+pub fn big(n: u64, k: u64) -> BigUint {
+    if k > n {
+        return BigUint::from(0u32);
+    }
+
+    let k = k.min(n - k);
+
+    (1..=k).fold(BigUint::one(), |result, i| {
+        result * BigUint::from(n - k + i) / BigUint::from(i)
+    })
+}
+
+// nCr mod p, for a prime p, via Lucas' theorem: the binomial is the product of the binomials of
+// the base-p digits of n and k.
+pub fn mod_prime(mut n: u64, mut k: u64, p: u64) -> u64 {
+    let mut result = 1 % p;
+
+    while k > 0 {
+        let (n_digit, k_digit) = (n % p, k % p);
+
+        if k_digit > n_digit {
+            return 0;
+        }
+
+        result = mul_mod(result, small_mod_prime(n_digit, k_digit, p), p);
+        n /= p;
+        k /= p;
+    }
+
+    result
+}
+
+// n, k < p
+fn small_mod_prime(n: u64, k: u64, p: u64) -> u64 {
+    let k = k.min(n - k);
+
+    let (numerator, denominator) = (1..=k).fold((1, 1), |(num, den), i| {
+        (mul_mod(num, n - k + i, p), mul_mod(den, i, p))
+    });
+
+    // Fermat's little theorem: den^(p - 2) is den's inverse
+    mul_mod(numerator, pow_mod(denominator, p - 2, p), p)
+}
+
+pub fn pascal_row(n: u64) -> Vec<BigUint> {
+    let mut row = Vec::with_capacity(n as usize + 1);
+    row.push(BigUint::one());
+
+    for k in 1..=n {
+        let next = row[k as usize - 1].clone() * BigUint::from(n + 1 - k) / BigUint::from(k);
+        row.push(next);
+    }
+
+    row
+}
+
+pub fn checked_pascal_row(n: u64) -> Option<Vec<u64>> {
+    let mut row = Vec::with_capacity(n as usize + 1);
+    row.push(1);
+
+    for k in 1..=n {
+        let previous = row[k as usize - 1] as u128;
+        let next = previous * (n + 1 - k) as u128 / k as u128;
+
+        if next > u64::MAX as u128 {
+            return None;
+        }
+
+        row.push(next as u64);
+    }
+
+    Some(row)
+}
+
+// Counts how many nCr, for 0 ≤ r ≤ n ≤ max_n, are greater than `threshold`.
+//
+// Each row is symmetrical and increases towards the middle, so it's enough to know the first r
+// where it goes over the threshold. That r never increases from one row to the next, so we can
+// walk it down the triangle, only ever computing binomials close to the threshold.
+pub fn count_exceeding(max_n: u64, threshold: u64) -> u64 {
+    let threshold = threshold as u128;
+    let mut count = 0;
+
+    // (k, nCk) for the first k in the current row where nCk > threshold
+    let mut boundary: Option<(u64, u128)> = None;
+
+    for n in 0..=max_n {
+        let (mut k, mut value) = match boundary {
+            Some((k, value)) => (k, value * (n as u128) / (n - k) as u128),
+
+            // The previous central binomial was within the threshold, so this one is at most four
+            // times that and fits comfortably.
+            None => match checked_u128(n as u128, (n / 2) as u128).unwrap() {
+                central if central <= threshold => continue,
+                central => (n / 2, central),
+            },
+        };
+
+        while k > 0 {
+            let before = value * k as u128 / (n - k + 1) as u128;
+
+            if before <= threshold {
+                break;
+            }
+
+            k -= 1;
+            value = before;
+        }
+
+        boundary = Some((k, value));
+        count += n + 1 - 2 * k;
+    }
+
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{big, checked_pascal_row, checked_u128, checked_u64, count_exceeding};
+    use super::{mod_prime, pascal_row};
+    use num::BigUint;
+
+    #[test]
+    fn test_small_binomials() {
+        assert_eq!(checked_u64(5, 3), Some(10));
+        assert_eq!(checked_u64(23, 10), Some(1_144_066));
+        assert_eq!(checked_u64(40, 20), Some(137_846_528_820));
+        assert_eq!(checked_u64(3, 5), Some(0));
+        assert_eq!(checked_u64(0, 0), Some(1));
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(checked_u64(67, 33), Some(14_226_520_737_620_288_370));
+        assert_eq!(checked_u64(68, 34), None);
+        assert_eq!(checked_u128(68, 34), Some(28_453_041_475_240_576_740));
+        assert_eq!(checked_u128(200, 100), None);
+    }
+
+    #[test]
+    fn test_big_binomials() {
+        let expected: BigUint = "90548514656103281165404177077484163874504589675413336841320"
+            .parse()
+            .unwrap();
+
+        assert_eq!(big(200, 100), expected);
+        assert_eq!(big(68, 34), BigUint::from(28_453_041_475_240_576_740u128));
+    }
+
+    #[test]
+    fn test_lucas() {
+        for &p in &[2, 3, 7, 13] {
+            for n in 0..40 {
+                for k in 0..=n {
+                    let expected = big(n, k) % BigUint::from(p);
+                    assert_eq!(BigUint::from(mod_prime(n, k, p)), expected);
+                }
+            }
+        }
+
+        assert_eq!(mod_prime(1000, 500, 1_000_000_007), 159_835_829);
+    }
+
+    #[test]
+    fn test_pascal_rows() {
+        let row: Vec<_> = pascal_row(5).into_iter().map(|n| n.to_string()).collect();
+        assert_eq!(row, vec!["1", "5", "10", "10", "5", "1"]);
+
+        assert_eq!(checked_pascal_row(4), Some(vec![1, 4, 6, 4, 1]));
+        assert_eq!(checked_pascal_row(67).map(|row| row.len()), Some(68));
+        assert_eq!(checked_pascal_row(68), None);
+    }
+
+    #[test]
+    fn test_count_exceeding() {
+        assert_eq!(count_exceeding(100, 1_000_000), 4075);
+        assert_eq!(count_exceeding(23, 1_000_000), 4);
+
+        for max_n in 0..30 {
+            for &threshold in &[0, 1, 5, 19, 20, 1000] {
+                let expected = (0..=max_n)
+                    .flat_map(|n| (0..=n).map(move |k| checked_u64(n, k).unwrap()))
+                    .filter(|&value| value > threshold)
+                    .count() as u64;
+
+                assert_eq!(count_exceeding(max_n, threshold), expected);
+            }
+        }
+    }
+}
@@ -45,3 +45,476 @@ impl<A> Combinations<A> {
         };
     }
 }
+
+// Iterators over sets of indices into `0..n`, each visited in ascending order and by reference, so
+// that no allocation happens after construction. The `Iterator` impls are there for convenience,
+// and allocate a `Vec` per item.
+pub trait LendingCombinations {
+    fn next_indices(&mut self) -> Option<&[usize]>;
+
+    fn map_values<A: Clone>(self, values: &[A]) -> MappedValues<'_, Self, A>
+    where
+        Self: Sized,
+    {
+        MappedValues {
+            indices: self,
+            values,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+macro_rules! vec_iterator_impl(
+    ($ty:ident) => (
+        impl Iterator for $ty {
+            type Item = Vec<usize>;
+
+            fn next(&mut self) -> Option<Vec<usize>> {
+                self.next_indices().map(|indices| indices.to_vec())
+            }
+        }
+    )
+);
+
+// All k-element subsets of `0..n`, in lexicographic order.
+pub struct KSubsets {
+    n: usize,
+    indices: Vec<usize>,
+    first: bool,
+    done: bool,
+}
+
+pub fn k_subsets(n: usize, k: usize) -> KSubsets {
+    KSubsets {
+        n,
+        indices: (0..k).collect(),
+        first: true,
+        done: k > n,
+    }
+}
+
+impl LendingCombinations for KSubsets {
+    fn next_indices(&mut self) -> Option<&[usize]> {
+        if self.done {
+            return None;
+        }
+
+        if self.first {
+            self.first = false;
+            return Some(&self.indices);
+        }
+
+        let k = self.indices.len();
+        let n = self.n;
+
+        match (0..k).rev().find(|&i| self.indices[i] < n - k + i) {
+            Some(i) => {
+                self.indices[i] += 1;
+
+                for j in i + 1..k {
+                    self.indices[j] = self.indices[j - 1] + 1;
+                }
+
+                Some(&self.indices)
+            }
+
+            None => {
+                self.done = true;
+                None
+            }
+        }
+    }
+}
+
+vec_iterator_impl!(KSubsets);
+
+// All k-element multisets of `0..n` (combinations with replacement), as non-decreasing index
+// sequences in lexicographic order.
+pub struct KMultisets {
+    n: usize,
+    indices: Vec<usize>,
+    first: bool,
+    done: bool,
+}
+
+pub fn k_multisets(n: usize, k: usize) -> KMultisets {
+    KMultisets {
+        n,
+        indices: vec![0; k],
+        first: true,
+        done: n == 0 && k > 0,
+    }
+}
+
+impl LendingCombinations for KMultisets {
+    fn next_indices(&mut self) -> Option<&[usize]> {
+        if self.done {
+            return None;
+        }
+
+        if self.first {
+            self.first = false;
+            return Some(&self.indices);
+        }
+
+        let n = self.n;
+
+        match self.indices.iter().rposition(|&index| index < n - 1) {
+            Some(i) => {
+                let new_index = self.indices[i] + 1;
+
+                for index in &mut self.indices[i..] {
+                    *index = new_index;
+                }
+
+                Some(&self.indices)
+            }
+
+            None => {
+                self.done = true;
+                None
+            }
+        }
+    }
+}
+
+vec_iterator_impl!(KMultisets);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SubsetOrder {
+    // Subset `i` is the one whose bitmask is `i`
+    Binary,
+
+    // Reflected binary Gray code: each subset differs from the previous one by a single element
+    Gray,
+}
+
+// All 2ⁿ subsets of `0..n`, starting from the empty set.
+pub struct PowerSet {
+    n: usize,
+    order: SubsetOrder,
+    counter: u64,
+    mask: u64,
+    indices: Vec<usize>,
+}
+
+pub fn power_set(n: usize, order: SubsetOrder) -> PowerSet {
+    assert!(n < 64, "power sets are limited to 63 elements");
+
+    PowerSet {
+        n,
+        order,
+        counter: 0,
+        mask: 0,
+        indices: Vec::with_capacity(n),
+    }
+}
+
+impl PowerSet {
+    // Bitmask of the subset last returned by `next_indices`
+    pub fn mask(&self) -> u64 {
+        self.mask
+    }
+
+    fn set_mask(&mut self, mask: u64) {
+        self.mask = mask;
+        self.indices.clear();
+        self.indices
+            .extend((0..self.n).filter(|&index| mask & (1 << index) != 0));
+    }
+
+    fn toggle(&mut self, index: usize) {
+        self.mask ^= 1 << index;
+
+        match self.indices.binary_search(&index) {
+            Ok(position) => {
+                self.indices.remove(position);
+            }
+
+            Err(position) => self.indices.insert(position, index),
+        }
+    }
+}
+
+impl LendingCombinations for PowerSet {
+    fn next_indices(&mut self) -> Option<&[usize]> {
+        if self.counter >= 1 << self.n {
+            return None;
+        }
+
+        let counter = self.counter;
+        self.counter += 1;
+
+        if counter > 0 {
+            match self.order {
+                SubsetOrder::Binary => self.set_mask(counter),
+                SubsetOrder::Gray => self.toggle(counter.trailing_zeros() as usize),
+            }
+        }
+
+        Some(&self.indices)
+    }
+}
+
+vec_iterator_impl!(PowerSet);
+
+// All k-element subsets of `0..n` in revolving-door order, where each subset is obtained from the
+// previous one by swapping a single element for another.
+//
+// Knuth, TAOCP 7.2.1.3, Algorithm R
+pub struct RevolvingDoor {
+    // c₁ < c₂ < ... < cₖ, followed by n as a sentinel
+    indices: Vec<usize>,
+    k: usize,
+    first: bool,
+    done: bool,
+}
+
+pub fn revolving_door(n: usize, k: usize) -> RevolvingDoor {
+    let mut indices: Vec<usize> = (0..k).collect();
+    indices.push(n);
+
+    RevolvingDoor {
+        indices,
+        k,
+        first: true,
+        done: k > n,
+    }
+}
+
+impl RevolvingDoor {
+    // Algorithm R indexes from 1
+    fn c(&self, j: usize) -> usize {
+        self.indices[j - 1]
+    }
+
+    fn set_c(&mut self, j: usize, value: usize) {
+        self.indices[j - 1] = value;
+    }
+
+    fn advance(&mut self) -> bool {
+        let k = self.k;
+
+        if k == 0 {
+            return false;
+        }
+
+        let mut j = 2;
+        let mut try_decrease = if k % 2 == 1 {
+            if self.c(1) + 1 < self.c(2) {
+                self.set_c(1, self.c(1) + 1);
+                return true;
+            }
+
+            true
+        } else {
+            if self.c(1) > 0 {
+                self.set_c(1, self.c(1) - 1);
+                return true;
+            }
+
+            false
+        };
+
+        while j <= k {
+            if try_decrease {
+                if self.c(j) >= j {
+                    self.set_c(j, self.c(j - 1));
+                    self.set_c(j - 1, j - 2);
+                    return true;
+                }
+            } else if self.c(j) + 1 < self.c(j + 1) {
+                self.set_c(j - 1, self.c(j));
+                self.set_c(j, self.c(j) + 1);
+                return true;
+            }
+
+            j += 1;
+            try_decrease = !try_decrease;
+        }
+
+        false
+    }
+}
+
+impl LendingCombinations for RevolvingDoor {
+    fn next_indices(&mut self) -> Option<&[usize]> {
+        if self.done {
+            return None;
+        }
+
+        if self.first {
+            self.first = false;
+        } else if !self.advance() {
+            self.done = true;
+            return None;
+        }
+
+        Some(&self.indices[..self.k])
+    }
+}
+
+vec_iterator_impl!(RevolvingDoor);
+
+pub struct MappedValues<'a, I, A> {
+    indices: I,
+    values: &'a [A],
+    buffer: Vec<A>,
+}
+
+impl<'a, I: LendingCombinations, A: Clone> MappedValues<'a, I, A> {
+    pub fn next_values(&mut self) -> Option<&[A]> {
+        let values = self.values;
+        let indices = self.indices.next_indices()?;
+
+        self.buffer.clear();
+        self.buffer
+            .extend(indices.iter().map(|&index| values[index].clone()));
+
+        Some(&self.buffer)
+    }
+}
+
+impl<'a, I: LendingCombinations, A: Clone> Iterator for MappedValues<'a, I, A> {
+    type Item = Vec<A>;
+
+    fn next(&mut self) -> Option<Vec<A>> {
+        self.next_values().map(|values| values.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SubsetOrder::{Binary, Gray};
+    use super::{k_multisets, k_subsets, power_set, revolving_door, LendingCombinations};
+
+    #[test]
+    fn test_k_subsets() {
+        let subsets: Vec<_> = k_subsets(4, 2).collect();
+
+        assert_eq!(
+            subsets,
+            vec![
+                vec![0, 1],
+                vec![0, 2],
+                vec![0, 3],
+                vec![1, 2],
+                vec![1, 3],
+                vec![2, 3],
+            ]
+        );
+
+        assert_eq!(k_subsets(10, 4).count(), 210);
+        assert_eq!(k_subsets(3, 0).count(), 1);
+        assert_eq!(k_subsets(3, 3).count(), 1);
+        assert_eq!(k_subsets(3, 4).count(), 0);
+    }
+
+    #[test]
+    fn test_k_multisets() {
+        let multisets: Vec<_> = k_multisets(3, 2).collect();
+
+        assert_eq!(
+            multisets,
+            vec![
+                vec![0, 0],
+                vec![0, 1],
+                vec![0, 2],
+                vec![1, 1],
+                vec![1, 2],
+                vec![2, 2],
+            ]
+        );
+
+        assert_eq!(k_multisets(5, 3).count(), 35);
+        assert_eq!(k_multisets(0, 2).count(), 0);
+        assert_eq!(k_multisets(0, 0).count(), 1);
+    }
+
+    #[test]
+    fn test_power_set() {
+        let subsets: Vec<_> = power_set(3, Binary).collect();
+
+        assert_eq!(
+            subsets,
+            vec![
+                vec![],
+                vec![0],
+                vec![1],
+                vec![0, 1],
+                vec![2],
+                vec![0, 2],
+                vec![1, 2],
+                vec![0, 1, 2],
+            ]
+        );
+
+        assert_eq!(power_set(0, Binary).count(), 1);
+    }
+
+    #[test]
+    fn test_gray_code_power_set() {
+        let mut subsets = power_set(4, Gray);
+        let mut masks = Vec::new();
+
+        while let Some(indices) = subsets.next_indices() {
+            let mask = indices.iter().fold(0, |mask, &index| mask | 1 << index);
+            assert_eq!(mask, subsets.mask());
+            masks.push(mask);
+        }
+
+        assert_eq!(masks.len(), 16);
+
+        for pair in masks.windows(2) {
+            assert_eq!((pair[0] ^ pair[1]).count_ones(), 1);
+        }
+
+        masks.sort();
+        masks.dedup();
+        assert_eq!(masks.len(), 16);
+    }
+
+    #[test]
+    fn test_revolving_door() {
+        for n in 0..8 {
+            for k in 0..=n {
+                let subsets: Vec<_> = revolving_door(n, k).collect();
+                let lexicographic: Vec<_> = k_subsets(n, k).collect();
+
+                let mut sorted = subsets.clone();
+                sorted.sort();
+                assert_eq!(sorted, lexicographic);
+
+                for pair in subsets.windows(2) {
+                    let removed = pair[0].iter().filter(|i| !pair[1].contains(i)).count();
+                    assert_eq!(removed, 1);
+                }
+            }
+        }
+
+        let subsets: Vec<_> = revolving_door(4, 2).collect();
+
+        assert_eq!(
+            subsets,
+            vec![
+                vec![0, 1],
+                vec![1, 2],
+                vec![0, 2],
+                vec![2, 3],
+                vec![1, 3],
+                vec![0, 3],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_map_values() {
+        let names = ["a", "b", "c"];
+        let mut pairs = k_subsets(3, 2).map_values(&names);
+
+        assert_eq!(pairs.next_values(), Some(&["a", "b"][..]));
+
+        let rest: Vec<_> = pairs.collect();
+        assert_eq!(rest, vec![vec!["a", "c"], vec!["b", "c"]]);
+    }
+}
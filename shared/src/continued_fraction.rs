@@ -1,5 +1,7 @@
-use hashbrown::HashSet;
-use num::{self, Float, ToPrimitive};
+use crate::roots;
+use hashbrown::HashMap;
+use num::rational::Ratio;
+use num::{self, Integer, ToPrimitive};
 
 #[derive(Debug, PartialEq)]
 pub enum FractionType {
@@ -8,136 +10,307 @@ pub enum FractionType {
 }
 
 pub fn divide_square(number: u32) -> FractionType {
-    let closest_square: u32 = {
-        let square_root = Float::sqrt(number as f32);
+    match expand_surd(0, number as u64, 1) {
+        Expansion::Finite(_) => FractionType::Exact,
 
-        if square_root.trunc() == square_root {
-            return FractionType::Exact;
+        Expansion::Periodic { pre_period, period } => {
+            let period = period.into_iter().map(|term| term as u32).collect();
+            FractionType::Periodic(pre_period[0] as u32, period)
         }
+    }
+}
 
-        square_root.floor() as u32
-    };
+#[derive(Debug, PartialEq)]
+pub enum Expansion<T> {
+    // The number is rational, so its expansion terminates
+    Finite(Vec<T>),
 
-    let mut period = Vec::with_capacity(10);
+    Periodic { pre_period: Vec<T>, period: Vec<T> },
+}
 
-    let mut prev_step = Step {
-        closest_square: closest_square,
-        number: number,
-        numerator: 1,
-        rest: closest_square,
-    };
+// Expands the non-negative quadratic surd (p + √d)/q into a continued fraction.
+//
+// Every step produces another surd of the same form, (P + √d)/Q, so the expansion becomes periodic
+// as soon as some (P, Q) pair repeats (which is guaranteed by Lagrange's theorem).
+//
+// Panics if the intermediate values don't fit in an i128. That can only happen when q doesn't divide
+// d - p² (see `normalize`) and d·q² or p·q is close to 2¹²⁷.
+pub fn expand_surd(p: i64, d: u64, q: i64) -> Expansion<u64> {
+    assert!(q != 0);
 
-    let mut seen_steps = HashSet::with_capacity(10);
+    let root = roots::isqrt(d as u128) as i128;
+
+    if root * root == d as i128 {
+        return Expansion::Finite(expand_rational(p as i128 + root, q as i128));
+    }
+
+    let mut state = normalize(p as i128, d as i128, q as i128);
+    let root = roots::isqrt(state.d as u128) as i128;
+    let mut terms = Vec::new();
+    let mut seen_states = HashMap::new();
 
     loop {
-        let (step_result, next_step) = step(prev_step);
+        if let Some(&period_start) = seen_states.get(&state) {
+            let period = terms.split_off(period_start);
 
-        if seen_steps.contains(&next_step) {
-            break;
+            return Expansion::Periodic {
+                pre_period: terms,
+                period,
+            };
         }
 
-        prev_step = next_step.clone();
-        seen_steps.insert(next_step);
-        period.push(step_result);
+        seen_states.insert(state, terms.len());
+
+        let (term, next_state) = step(state, root);
+        assert!(term >= 0, "only non-negative surds can be expanded");
+
+        terms.push(term as u64);
+        state = next_state;
     }
+}
 
-    FractionType::Periodic(closest_square, period)
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+struct Surd {
+    p: i128,
+    q: i128,
+    d: i128,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
-struct Step {
-    closest_square: u32,
-    numerator: u32,
-    number: u32,
-    rest: u32,
+const TOO_LARGE: &str = "(p + √d)/q is too large to expand";
+
+// The recurrence in `step` only stays within the integers if q divides d - p². Multiplying
+// everything by |q| makes that true without changing the value.
+//
+// p and q come from i64s, so only d·q² can overflow here.
+fn normalize(p: i128, d: i128, q: i128) -> Surd {
+    if (d - p * p) % q == 0 {
+        Surd { p, q, d }
+    } else {
+        let factor = q.abs();
+        let d = factor
+            .checked_mul(factor)
+            .and_then(|square| square.checked_mul(d))
+            .expect(TOO_LARGE);
+
+        Surd {
+            p: p * factor,
+            q: q * factor,
+            d,
+        }
+    }
 }
 
-fn step(prev: Step) -> (u32, Step) {
-    let numerator = (prev.number - (prev.rest * prev.rest)) / prev.numerator;
+// Splits (P + √d)/Q into its integer part a and 1/((P' + √d)/Q'), where `root` is ⌊√d⌋.
+fn step(prev: Surd, root: i128) -> (i128, Surd) {
+    // √d is irrational, so it lies strictly between root and root + 1
+    let term = if prev.q > 0 {
+        floor_div(prev.p + root, prev.q)
+    } else {
+        floor_div(prev.p + root + 1, prev.q)
+    };
+
+    let p = term * prev.q - prev.p;
+    let q = (prev.d - p.checked_mul(p).expect(TOO_LARGE)) / prev.q;
 
-    let multiplier = (0..)
-        .step_by(1)
-        .take_while(|&n| n * numerator <= prev.closest_square + prev.rest)
-        .last()
-        .unwrap();
+    (term, Surd { p, q, d: prev.d })
+}
 
-    let new_rest = (multiplier * numerator) - prev.rest;
+fn expand_rational(mut numerator: i128, mut denominator: i128) -> Vec<u64> {
+    let mut terms = Vec::new();
 
-    (
-        multiplier,
-        Step {
-            closest_square: prev.closest_square,
-            number: prev.number,
-            numerator: numerator,
-            rest: new_rest,
-        },
-    )
+    loop {
+        let term = floor_div(numerator, denominator);
+        assert!(
+            term >= 0 || !terms.is_empty(),
+            "only non-negative numbers can be expanded"
+        );
+        terms.push(term as u64);
+
+        let remainder = numerator - term * denominator;
+        if remainder == 0 {
+            return terms;
+        }
+
+        numerator = denominator;
+        denominator = remainder;
+    }
 }
 
-#[derive(Clone, Copy, Debug)]
-pub struct ConvergentPair<T> {
-    pub numerator: T,
-    pub denominator: T,
+fn floor_div(a: i128, b: i128) -> i128 {
+    let quotient = a / b;
+
+    if (a % b != 0) && ((a < 0) != (b < 0)) {
+        quotient - 1
+    } else {
+        quotient
+    }
 }
 
-pub struct ConvergentIterator<T> {
-    period: Vec<T>,
-    index: usize,
-    next_a: T,
-    prev_pair: ConvergentPair<T>,
-    pprev_pair: ConvergentPair<T>,
+// A (possibly infinite) continued fraction [a₀; a₁, a₂, ...], given by its partial quotients.
+pub struct ContinuedFraction<I> {
+    terms: I,
 }
 
-pub fn convergent_iterator<T>(number: T) -> ConvergentIterator<T>
+pub fn from_terms<I>(terms: I) -> ContinuedFraction<I::IntoIter>
 where
-    T: ToPrimitive + From<u32>,
+    I: IntoIterator<Item = u64>,
 {
-    match divide_square(number.to_u32().unwrap()) {
-        FractionType::Exact => unimplemented!(),
+    ContinuedFraction {
+        terms: terms.into_iter(),
+    }
+}
 
-        FractionType::Periodic(n, period) => {
-            let period = period.into_iter().map(From::from).collect();
+pub fn rational(numerator: u64, denominator: u64) -> ContinuedFraction<impl Iterator<Item = u64>> {
+    assert!(denominator != 0);
+    from_terms(expand_rational(numerator as i128, denominator as i128))
+}
 
-            ConvergentIterator {
-                index: 0,
-                period,
-                next_a: From::from(n),
-                prev_pair: ConvergentPair {
-                    numerator: From::from(1),
-                    denominator: From::from(0),
-                },
+pub fn periodic(
+    pre_period: Vec<u64>,
+    period: Vec<u64>,
+) -> ContinuedFraction<impl Iterator<Item = u64>> {
+    from_terms(pre_period.into_iter().chain(period.into_iter().cycle()))
+}
 
-                pprev_pair: ConvergentPair {
-                    numerator: From::from(0),
-                    denominator: From::from(1),
-                },
-            }
+// (p + √d)/q, which terminates if d is a perfect square
+pub fn surd(p: i64, d: u64, q: i64) -> ContinuedFraction<impl Iterator<Item = u64>> {
+    let (pre_period, period) = match expand_surd(p, d, q) {
+        Expansion::Finite(terms) => (terms, vec![]),
+        Expansion::Periodic { pre_period, period } => (pre_period, period),
+    };
+
+    periodic(pre_period, period)
+}
+
+pub fn square_root(number: u64) -> ContinuedFraction<impl Iterator<Item = u64>> {
+    surd(0, number, 1)
+}
+
+// e = [2; 1, 2, 1, 1, 4, 1, 1, 6, ...]
+pub fn e() -> ContinuedFraction<impl Iterator<Item = u64>> {
+    from_terms((0..).map(|index| match index {
+        0 => 2,
+        index if index % 3 == 2 => 2 * (index + 1) / 3,
+        _ => 1,
+    }))
+}
+
+// tan(1) = [1; 1, 1, 3, 1, 5, 1, 7, ...]
+pub fn tan_one() -> ContinuedFraction<impl Iterator<Item = u64>> {
+    from_terms((0..).map(|index| if index % 2 == 1 { index } else { 1 }))
+}
+
+impl<I: Iterator<Item = u64>> ContinuedFraction<I> {
+    pub fn terms(self) -> I {
+        self.terms
+    }
+
+    pub fn truncate(self, len: usize) -> ContinuedFraction<std::iter::Take<I>> {
+        from_terms(self.terms.take(len))
+    }
+
+    pub fn convergents<T>(self) -> ConvergentIterator<T, I>
+    where
+        T: From<u64>,
+    {
+        ConvergentIterator {
+            terms: self.terms,
+            prev_pair: ConvergentPair {
+                numerator: T::from(1),
+                denominator: T::from(0),
+            },
+
+            pprev_pair: ConvergentPair {
+                numerator: T::from(0),
+                denominator: T::from(1),
+            },
+
+            overflowed: false,
+        }
+    }
+
+    // The exact value of a finite continued fraction, or None if it doesn't fit in T. Infinite
+    // ones need to be truncated first.
+    pub fn to_ratio<T>(self) -> Option<Ratio<T>>
+    where
+        T: From<u64> + Clone + Integer + num::CheckedMul + num::CheckedAdd,
+    {
+        let mut convergents = self.convergents::<T>();
+        let last = convergents
+            .by_ref()
+            .last()
+            .expect("empty continued fraction");
+
+        if convergents.overflowed() {
+            None
+        } else {
+            Some(last.into())
         }
     }
 }
 
-impl<T> Iterator for ConvergentIterator<T>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConvergentPair<T> {
+    pub numerator: T,
+    pub denominator: T,
+}
+
+impl<T: Clone + Integer> From<ConvergentPair<T>> for Ratio<T> {
+    fn from(pair: ConvergentPair<T>) -> Ratio<T> {
+        Ratio::new(pair.numerator, pair.denominator)
+    }
+}
+
+// Yields each convergent of a continued fraction, stopping early if T overflows.
+pub struct ConvergentIterator<T, I> {
+    terms: I,
+    prev_pair: ConvergentPair<T>,
+    pprev_pair: ConvergentPair<T>,
+    overflowed: bool,
+}
+
+impl<T, I> ConvergentIterator<T, I> {
+    // Whether the iterator stopped because the next convergent didn't fit in T, rather than
+    // because the continued fraction ended
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+}
+
+pub fn convergent_iterator<T>(number: T) -> ConvergentIterator<T, impl Iterator<Item = u64>>
 where
-    T: Clone + num::CheckedMul + num::CheckedAdd,
+    T: ToPrimitive + From<u64>,
+{
+    square_root(number.to_u64().unwrap()).convergents()
+}
+
+impl<T, I> Iterator for ConvergentIterator<T, I>
+where
+    T: Clone + From<u64> + num::CheckedMul + num::CheckedAdd,
+    I: Iterator<Item = u64>,
 {
     type Item = ConvergentPair<T>;
 
     fn next(&mut self) -> Option<ConvergentPair<T>> {
         use std::mem::swap;
 
+        if self.overflowed {
+            return None;
+        }
+
         let pair = {
-            let a = &self.next_a;
+            let a = T::from(self.terms.next()?);
 
             let numerator = self
                 .prev_pair
                 .numerator
-                .checked_mul(a)
+                .checked_mul(&a)
                 .and_then(|m| m.checked_add(&self.pprev_pair.numerator));
 
             let denominator = self
                 .prev_pair
                 .denominator
-                .checked_mul(a)
+                .checked_mul(&a)
                 .and_then(|m| m.checked_add(&self.pprev_pair.denominator));
 
             match (numerator, denominator) {
@@ -146,12 +319,13 @@ where
                     denominator: d,
                 },
 
-                _ => return None,
+                _ => {
+                    self.overflowed = true;
+                    return None;
+                }
             }
         };
 
-        self.next_a = self.period[self.index % self.period.len()].clone();
-        self.index += 1;
         swap(&mut self.pprev_pair, &mut self.prev_pair);
         self.prev_pair = pair.clone();
 
@@ -161,46 +335,220 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::FractionType::{Exact, Periodic};
-    use super::{divide_square, step, Step};
-    use num::Float;
+    use super::Expansion::{Finite, Periodic};
+    use super::FractionType::{Exact, Periodic as PeriodicSquare};
+    use super::{convergent_iterator, divide_square, e, expand_surd, from_terms, periodic};
+    use super::{rational, square_root, step, tan_one, ConvergentPair, Surd};
+    use num::rational::Ratio;
+    use num::BigUint;
 
     #[test]
     fn test_example_divisions() {
-        assert_eq!(divide_square(2), Periodic(1, vec![2]));
-        assert_eq!(divide_square(3), Periodic(1, vec![1, 2]));
+        assert_eq!(divide_square(2), PeriodicSquare(1, vec![2]));
+        assert_eq!(divide_square(3), PeriodicSquare(1, vec![1, 2]));
         assert_eq!(divide_square(4), Exact);
-        assert_eq!(divide_square(5), Periodic(2, vec![4]));
-        assert_eq!(divide_square(6), Periodic(2, vec![2, 4]));
-        assert_eq!(divide_square(7), Periodic(2, vec![1, 1, 1, 4]));
-        assert_eq!(divide_square(8), Periodic(2, vec![1, 4]));
+        assert_eq!(divide_square(5), PeriodicSquare(2, vec![4]));
+        assert_eq!(divide_square(6), PeriodicSquare(2, vec![2, 4]));
+        assert_eq!(divide_square(7), PeriodicSquare(2, vec![1, 1, 1, 4]));
+        assert_eq!(divide_square(8), PeriodicSquare(2, vec![1, 4]));
         assert_eq!(divide_square(9), Exact);
-        assert_eq!(divide_square(10), Periodic(3, vec![6]));
-        assert_eq!(divide_square(11), Periodic(3, vec![3, 6]));
-        assert_eq!(divide_square(12), Periodic(3, vec![2, 6]));
-        assert_eq!(divide_square(13), Periodic(3, vec![1, 1, 1, 1, 6]));
+        assert_eq!(divide_square(10), PeriodicSquare(3, vec![6]));
+        assert_eq!(divide_square(11), PeriodicSquare(3, vec![3, 6]));
+        assert_eq!(divide_square(12), PeriodicSquare(3, vec![2, 6]));
+        assert_eq!(divide_square(13), PeriodicSquare(3, vec![1, 1, 1, 1, 6]));
     }
 
-    fn def_step(numerator: u32, number: u32, rest: u32) -> Step {
-        let closest_square = Float::sqrt(number as f32).floor() as u32;
+    #[test]
+    fn test_large_squares() {
+        assert_eq!(divide_square(65535 * 65535), Exact);
+        assert_eq!(
+            divide_square(65535 * 65535 + 1),
+            PeriodicSquare(65535, vec![131070])
+        );
+        assert_eq!(
+            divide_square(65535 * 65535 - 1),
+            PeriodicSquare(65534, vec![1, 131068])
+        );
+    }
 
-        Step {
-            numerator: numerator,
-            number: number,
-            rest: rest,
-            closest_square: closest_square,
-        }
+    fn def_step(p: i128, q: i128) -> Surd {
+        Surd { p, q, d: 23 }
     }
 
     #[test]
     fn test_division_steps() {
-        assert_eq!(step(def_step(1, 23, 4)), (1, def_step(7, 23, 3)));
-        assert_eq!(step(def_step(7, 23, 3)), (3, def_step(2, 23, 3)));
-        assert_eq!(step(def_step(2, 23, 3)), (1, def_step(7, 23, 4)));
-        assert_eq!(step(def_step(7, 23, 4)), (8, def_step(1, 23, 4)));
-        assert_eq!(step(def_step(1, 23, 4)), (1, def_step(7, 23, 3)));
-        assert_eq!(step(def_step(7, 23, 3)), (3, def_step(2, 23, 3)));
-        assert_eq!(step(def_step(2, 23, 3)), (1, def_step(7, 23, 4)));
-        assert_eq!(step(def_step(7, 23, 4)), (8, def_step(1, 23, 4)));
+        assert_eq!(step(def_step(4, 7), 4), (1, def_step(3, 2)));
+        assert_eq!(step(def_step(3, 2), 4), (3, def_step(3, 7)));
+        assert_eq!(step(def_step(3, 7), 4), (1, def_step(4, 1)));
+        assert_eq!(step(def_step(4, 1), 4), (8, def_step(4, 7)));
+        assert_eq!(step(def_step(4, 7), 4), (1, def_step(3, 2)));
+        assert_eq!(step(def_step(3, 2), 4), (3, def_step(3, 7)));
+        assert_eq!(step(def_step(3, 7), 4), (1, def_step(4, 1)));
+        assert_eq!(step(def_step(4, 1), 4), (8, def_step(4, 7)));
+    }
+
+    #[test]
+    fn test_surds() {
+        // Golden ratio
+        assert_eq!(
+            expand_surd(1, 5, 2),
+            Periodic {
+                pre_period: vec![],
+                period: vec![1]
+            }
+        );
+
+        assert_eq!(
+            expand_surd(1, 13, 2),
+            Periodic {
+                pre_period: vec![2],
+                period: vec![3]
+            }
+        );
+
+        // 1/√2 = [0; 1, 2, 2, ...]
+        assert_eq!(
+            expand_surd(0, 2, 2),
+            Periodic {
+                pre_period: vec![0, 1],
+                period: vec![2]
+            }
+        );
+
+        // (-2 + √3)/(-1) = 2 - √3 = [0; 3, 1, 2, 1, 2, ...]
+        assert_eq!(
+            expand_surd(-2, 3, -1),
+            Periodic {
+                pre_period: vec![0, 3],
+                period: vec![1, 2]
+            }
+        );
+
+        assert_eq!(
+            expand_surd(0, 23, 1),
+            Periodic {
+                pre_period: vec![4],
+                period: vec![1, 3, 1, 8]
+            }
+        );
+    }
+
+    #[test]
+    fn test_normalized_surds() {
+        // 3 doesn't divide 2 - 1², so this is expanded as (3 + √18)/9
+        assert_eq!(
+            expand_surd(1, 2, 3),
+            Periodic {
+                pre_period: vec![0, 1],
+                period: vec![4, 8]
+            }
+        );
+    }
+
+    #[test]
+    fn test_rational_surds() {
+        assert_eq!(expand_surd(1, 9, 2), Finite(vec![2]));
+        assert_eq!(expand_surd(3, 16, 5), Finite(vec![1, 2, 2]));
+        assert_eq!(expand_surd(-3, 16, 5), Finite(vec![0, 5]));
+    }
+
+    fn pairs(convergents: &[(u64, u64)]) -> Vec<ConvergentPair<u64>> {
+        convergents
+            .iter()
+            .map(|&(numerator, denominator)| ConvergentPair {
+                numerator,
+                denominator,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_square_root_convergents() {
+        let convergents: Vec<_> = square_root(2).convergents::<u64>().take(4).collect();
+        assert_eq!(convergents, pairs(&[(1, 1), (3, 2), (7, 5), (17, 12)]));
+
+        let convergents: Vec<_> = convergent_iterator(16u128).collect();
+        assert_eq!(convergents.len(), 1);
+        assert_eq!(convergents[0].numerator, 4);
+    }
+
+    #[test]
+    fn test_e_convergents() {
+        let convergents: Vec<_> = e().convergents::<u64>().take(10).collect();
+
+        assert_eq!(
+            convergents,
+            pairs(&[
+                (2, 1),
+                (3, 1),
+                (8, 3),
+                (11, 4),
+                (19, 7),
+                (87, 32),
+                (106, 39),
+                (193, 71),
+                (1264, 465),
+                (1457, 536),
+            ])
+        );
+
+        let hundredth = e().convergents::<BigUint>().nth(99).unwrap();
+        let digit_sum: u32 = hundredth
+            .numerator
+            .to_string()
+            .chars()
+            .map(|digit| digit.to_digit(10).unwrap())
+            .sum();
+
+        assert_eq!(digit_sum, 272);
+    }
+
+    #[test]
+    fn test_tan_one() {
+        let terms: Vec<_> = tan_one().terms().take(8).collect();
+        assert_eq!(terms, vec![1, 1, 1, 3, 1, 5, 1, 7]);
+
+        let approximation: Ratio<u64> = tan_one().truncate(14).to_ratio().unwrap();
+        let value = *approximation.numer() as f64 / *approximation.denom() as f64;
+        assert!((value - 1f64.tan()).abs() < 1e-11);
+    }
+
+    #[test]
+    fn test_rationals() {
+        let terms: Vec<_> = rational(415, 93).terms().collect();
+        assert_eq!(terms, vec![4, 2, 6, 7]);
+
+        assert_eq!(
+            rational(415, 93).to_ratio::<u64>(),
+            Some(Ratio::new(415, 93))
+        );
+        assert_eq!(rational(6, 4).to_ratio::<u128>(), Some(Ratio::new(3, 2)));
+        assert_eq!(
+            from_terms(vec![0, 2]).to_ratio::<u64>(),
+            Some(Ratio::new(1, 2))
+        );
+    }
+
+    #[test]
+    fn test_overflowing_convergents() {
+        let golden = periodic(vec![], vec![1]);
+        let mut convergents = golden.convergents::<u128>();
+        let count = convergents.by_ref().count();
+
+        // The denominators are the Fibonacci numbers, and F(186) is the largest fitting in a u128
+        assert_eq!(count, 185);
+        assert!(convergents.overflowed());
+        assert_eq!(convergents.next(), None);
+
+        assert!(!rational(415, 93).convergents::<u64>().overflowed());
+        assert_eq!(from_terms(vec![u64::MAX; 3]).to_ratio::<u64>(), None);
+
+        // [m; m] = (m² + 1)/m still fits in a u128, but not in a u64
+        let max = u64::MAX as u128;
+        assert_eq!(from_terms(vec![u64::MAX; 2]).to_ratio::<u64>(), None);
+        assert_eq!(
+            from_terms(vec![u64::MAX; 2]).to_ratio::<u128>(),
+            Some(Ratio::new(max * max + 1, max))
+        );
     }
 }
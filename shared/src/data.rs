@@ -0,0 +1,343 @@
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::env;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str;
+
+// Points to a directory with the data files, overriding the ones in the workspace
+pub const DIRECTORY_VARIABLE: &str = "PROJECT_EULER_DATA";
+
+// The workspace's data directory, as it was when compiling
+const WORKSPACE_DIRECTORY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../data");
+
+struct DataFile {
+    problem: u32,
+    name: &'static str,
+    embedded: &'static [u8],
+}
+
+macro_rules! data_files {
+    ($(($problem:expr, $name:expr)),* $(,)*) => {
+        &[$(DataFile {
+            problem: $problem,
+            name: $name,
+            embedded: include_bytes!(concat!("../../data/", $name)),
+        }),*]
+    };
+}
+
+const DATA_FILES: &[DataFile] = data_files![
+    (8, "08-number.txt"),
+    (13, "13-numbers.txt"),
+    (22, "22-names.txt"),
+    (42, "42-words.txt"),
+    (54, "54-poker.txt"),
+    (59, "59-cipher.txt"),
+    (67, "67-triangle.txt"),
+    (79, "79-keylog.txt"),
+    (81, "81-matrix.txt"),
+    (82, "82-matrix.txt"),
+    (83, "83-matrix.txt"),
+];
+
+// Size and SHA-256 of every data file, so bad data is caught before it gives a wrong answer
+const MANIFEST: &str = include_str!("../../data/manifest.txt");
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub problem: u32,
+    pub name: &'static str,
+    pub size: usize,
+    pub sha256: &'static str,
+}
+
+pub fn manifest() -> Vec<ManifestEntry> {
+    MANIFEST
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+        .map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+
+            match fields[..] {
+                [problem, name, size, sha256] => ManifestEntry {
+                    problem: problem.parse().expect("Invalid problem in the manifest"),
+                    name,
+                    size: size.parse().expect("Invalid size in the manifest"),
+                    sha256,
+                },
+                _ => panic!("Malformed manifest line: {:?}", line),
+            }
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    UnknownProblem(u32),
+    Io {
+        path: PathBuf,
+        kind: io::ErrorKind,
+    },
+    NotUtf8 {
+        problem: u32,
+    },
+    // The file doesn't match the manifest
+    WrongSize {
+        source: Source,
+        expected: usize,
+        found: usize,
+    },
+    WrongChecksum {
+        source: Source,
+        expected: String,
+        found: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnknownProblem(problem) => write!(f, "Problem {} has no data file", problem),
+            Error::Io { path, kind } => write!(f, "Couldn't read {}: {:?}", path.display(), kind),
+            Error::NotUtf8 { problem } => write!(f, "The data for problem {} isn't text", problem),
+            Error::WrongSize {
+                source,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} has {} bytes instead of {}, so it's probably truncated or corrupted",
+                source, found, expected
+            ),
+            Error::WrongChecksum {
+                source,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} has SHA-256 {} instead of {}, so it was modified",
+                source, found, expected
+            ),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+// Where a problem's data is loaded from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    File(PathBuf),
+    // The bytes included in the binary, for when it runs away from the workspace
+    Embedded,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Embedded => write!(f, "The embedded data"),
+        }
+    }
+}
+
+fn data_file(problem: u32) -> Result<&'static DataFile, Error> {
+    DATA_FILES
+        .iter()
+        .find(|file| file.problem == problem)
+        .ok_or(Error::UnknownProblem(problem))
+}
+
+pub fn filename(problem: u32) -> Option<&'static str> {
+    data_file(problem).ok().map(|file| file.name)
+}
+
+// Looks in the directory from `DIRECTORY_VARIABLE` if it's set, then in the workspace, and falls
+// back to the embedded copy when the workspace isn't around anymore
+pub fn locate(problem: u32) -> Result<Source, Error> {
+    let directory = env::var_os(DIRECTORY_VARIABLE).map(PathBuf::from);
+    locate_in(
+        problem,
+        directory.as_deref(),
+        Path::new(WORKSPACE_DIRECTORY),
+    )
+}
+
+fn locate_in(problem: u32, directory: Option<&Path>, workspace: &Path) -> Result<Source, Error> {
+    let name = data_file(problem)?.name;
+
+    // An explicitly chosen directory is used even if the file is missing, so that mistakes show
+    // up as errors instead of silently using other data
+    if let Some(directory) = directory {
+        return Ok(Source::File(directory.join(name)));
+    }
+
+    let path = workspace.join(name);
+
+    if path.is_file() {
+        Ok(Source::File(path))
+    } else {
+        Ok(Source::Embedded)
+    }
+}
+
+// The data for a problem, after checking it against the manifest
+pub fn load_bytes(problem: u32) -> Result<Cow<'static, [u8]>, Error> {
+    let source = locate(problem)?;
+    let bytes = read(problem, source.clone())?;
+
+    verify(problem, source, &bytes)?;
+    Ok(bytes)
+}
+
+// The data for a problem, as text, wherever it was found
+pub fn load(problem: u32) -> Result<Cow<'static, str>, Error> {
+    let not_utf8 = |_| Error::NotUtf8 { problem };
+
+    match load_bytes(problem)? {
+        Cow::Borrowed(bytes) => str::from_utf8(bytes).map(Cow::Borrowed).map_err(not_utf8),
+        Cow::Owned(bytes) => String::from_utf8(bytes)
+            .map(Cow::Owned)
+            .map_err(|error| not_utf8(error.utf8_error())),
+    }
+}
+
+// Like `load`, for the solutions that can't do anything without their data. Panics with the
+// error's message rather than its Debug form, so that a bad file is easy to spot.
+pub fn load_or_panic(problem: u32) -> Cow<'static, str> {
+    load(problem).unwrap_or_else(|error| panic!("{}", error))
+}
+
+fn read(problem: u32, source: Source) -> Result<Cow<'static, [u8]>, Error> {
+    match source {
+        Source::Embedded => Ok(Cow::Borrowed(data_file(problem)?.embedded)),
+        Source::File(path) => match fs::read(&path) {
+            Ok(bytes) => Ok(Cow::Owned(bytes)),
+            Err(error) => Err(Error::Io {
+                path,
+                kind: error.kind(),
+            }),
+        },
+    }
+}
+
+pub fn verify(problem: u32, source: Source, bytes: &[u8]) -> Result<(), Error> {
+    let entry = manifest()
+        .into_iter()
+        .find(|entry| entry.problem == problem)
+        .ok_or(Error::UnknownProblem(problem))?;
+
+    if bytes.len() != entry.size {
+        return Err(Error::WrongSize {
+            source,
+            expected: entry.size,
+            found: bytes.len(),
+        });
+    }
+
+    let sha256 = format!("{:x}", Sha256::digest(bytes));
+
+    if sha256 != entry.sha256 {
+        return Err(Error::WrongChecksum {
+            source,
+            expected: entry.sha256.to_owned(),
+            found: sha256,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{filename, load, load_or_panic, locate_in, manifest, read, verify, Error, Source};
+    use super::{DATA_FILES, WORKSPACE_DIRECTORY};
+    use std::io;
+    use std::path::Path;
+
+    #[test]
+    fn test_locate() {
+        let workspace = Path::new(WORKSPACE_DIRECTORY);
+        let elsewhere = Path::new("/nonexistent");
+
+        assert_eq!(filename(22), Some("22-names.txt"));
+        assert_eq!(filename(1), None);
+
+        assert_eq!(
+            locate_in(22, None, workspace),
+            Ok(Source::File(workspace.join("22-names.txt")))
+        );
+        assert_eq!(
+            locate_in(22, Some(elsewhere), workspace),
+            Ok(Source::File(elsewhere.join("22-names.txt")))
+        );
+        assert_eq!(locate_in(22, None, elsewhere), Ok(Source::Embedded));
+        assert_eq!(locate_in(1, None, workspace), Err(Error::UnknownProblem(1)));
+    }
+
+    #[test]
+    fn test_load() {
+        assert!(load(22).unwrap().starts_with("\"MARY\",\"PATRICIA\""));
+        assert_eq!(load(1), Err(Error::UnknownProblem(1)));
+        assert_eq!(load_or_panic(42), load(42).unwrap());
+
+        let embedded = read(81, Source::Embedded).unwrap();
+        let workspace = read(
+            81,
+            Source::File(Path::new(WORKSPACE_DIRECTORY).join("81-matrix.txt")),
+        );
+        assert_eq!(workspace, Ok(embedded));
+
+        let missing = Path::new("/nonexistent/81-matrix.txt").to_owned();
+        assert_eq!(
+            read(81, Source::File(missing.clone())),
+            Err(Error::Io {
+                path: missing,
+                kind: io::ErrorKind::NotFound
+            })
+        );
+    }
+
+    #[test]
+    fn test_manifest() {
+        let entries = manifest();
+        assert_eq!(entries.len(), DATA_FILES.len());
+
+        for (entry, file) in entries.iter().zip(DATA_FILES) {
+            assert_eq!((entry.problem, entry.name), (file.problem, file.name));
+            assert_eq!(
+                verify(file.problem, Source::Embedded, file.embedded),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        let mut bytes = read(79, Source::Embedded).unwrap().into_owned();
+        let source = Source::File(Path::new("79-keylog.txt").to_owned());
+
+        assert_eq!(
+            verify(79, source.clone(), &bytes[..100]),
+            Err(Error::WrongSize {
+                source: source.clone(),
+                expected: 200,
+                found: 100
+            })
+        );
+
+        bytes[0] = b'1';
+        let error = verify(79, source, &bytes).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "79-keylog.txt has SHA-256 \
+             f213acbea5e228b617e73102cca871f7c82ab685fc7737798855448fefae5836 instead of \
+             91a449a22a107dca172ee4f5fd3330180188dd9fd3d8fd7417f4176a38609db0, so it was modified"
+        );
+    }
+}
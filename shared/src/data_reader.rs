@@ -1,38 +1,558 @@
-use std::borrow::ToOwned;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use num::BigUint;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::iter::{self, Peekable};
 use std::path::Path;
-use std::str;
+use std::str::{Chars, FromStr};
 
-pub struct DataReader {
-    reader: BufReader<File>,
+// Where a problem was found in a data file. Both start at 1, and columns count bytes, which is the
+// same as characters for the ASCII files under data/.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
 }
 
-impl Iterator for DataReader {
-    type Item = String;
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    Io(io::ErrorKind),
+    Empty,
+    Unexpected {
+        position: Position,
+        found: char,
+    },
+    // The text ended in the middle of an item, e.g. inside quotes or right after a comma
+    UnexpectedEnd {
+        position: Position,
+    },
+    InvalidNumber {
+        position: Position,
+        value: String,
+    },
+    InvalidCard {
+        position: Position,
+        value: String,
+    },
+    // A line with the wrong amount of items: matrix rows that differ from the first one, triangle
+    // rows that aren't one longer than the previous one, or hands with the wrong amount of cards
+    WrongLength {
+        position: Position,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(kind) => write!(f, "Couldn't read the data: {:?}", kind),
+            Error::Empty => write!(f, "There's no data"),
+            Error::Unexpected { position, found } => {
+                write!(f, "Unexpected {:?} at {}", found, position)
+            }
+            Error::UnexpectedEnd { position } => write!(f, "Unexpected end at {}", position),
+            Error::InvalidNumber { position, value } => {
+                write!(f, "Invalid number {:?} at {}", value, position)
+            }
+            Error::InvalidCard { position, value } => {
+                write!(f, "Invalid card {:?} at {}", value, position)
+            }
+            Error::WrongLength {
+                position,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Expected {} items at {}, but found {}",
+                expected, position, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Error {
+        Error::Io(error.kind())
+    }
+}
+
+pub fn read_file<P: AsRef<Path>>(path: P) -> Result<String, Error> {
+    Ok(fs::read_to_string(path)?)
+}
+
+// Comma-separated quoted words, as in data/22-names.txt: "MARY","PATRICIA","LINDA"
+pub fn words(text: &str) -> Result<Vec<String>, Error> {
+    let mut cursor = Cursor::new(text);
+    let mut words = Vec::new();
 
-    fn next(&mut self) -> Option<String> {
-        let mut buffer = Vec::new();
+    loop {
+        cursor.skip_whitespace();
 
-        return self
-            .reader
-            .read_until(',' as u8, &mut buffer)
-            .ok()
-            .and_then(|bytes| if bytes > 0 { trim_markup(buffer) } else { None });
+        let opening = cursor.position;
+        match cursor.bump() {
+            Some('"') => {}
+            Some(found) => {
+                return Err(Error::Unexpected {
+                    position: opening,
+                    found,
+                })
+            }
+            None if words.is_empty() => return Err(Error::Empty),
+            None => return Err(Error::UnexpectedEnd { position: opening }),
+        }
+
+        let mut word = String::new();
+
+        loop {
+            match cursor.bump() {
+                Some('"') => break,
+                Some(chr) => word.push(chr),
+                None => return Err(Error::UnexpectedEnd { position: opening }),
+            }
+        }
+
+        words.push(word);
+        cursor.skip_whitespace();
+
+        let position = cursor.position;
+        match cursor.bump() {
+            Some(',') => {}
+            Some(found) => return Err(Error::Unexpected { position, found }),
+            None => return Ok(words),
+        }
     }
 }
 
-fn trim_markup(bytes: Vec<u8>) -> Option<String> {
-    let chars: &[char] = &['"', ','];
-    str::from_utf8(&*bytes)
-        .ok()
-        .map(|slice| slice.trim_matches(chars).to_owned())
+// Comma-separated numbers, one row per line, as in data/81-matrix.txt. Every row must be as long
+// as the first one.
+pub fn matrix<T: FromStr>(text: &str) -> Result<Vec<Vec<T>>, Error> {
+    let mut rows: Vec<Vec<T>> = Vec::new();
+
+    for (line, content) in lines(text) {
+        let row = parse_fields(line, comma_fields(content))?;
+
+        if let Some(first) = rows.first() {
+            if first.len() != row.len() {
+                return Err(Error::WrongLength {
+                    position: Position { line, column: 1 },
+                    expected: first.len(),
+                    found: row.len(),
+                });
+            }
+        }
+
+        rows.push(row);
+    }
+
+    non_empty(rows)
 }
 
-pub fn for_path(path_str: &str) -> DataReader {
-    let path = Path::new(path_str);
-    let file = File::open(path).unwrap();
-    let reader = BufReader::new(file);
+// Whitespace-separated numbers, where the nth line has n of them, as in data/67-triangle.txt
+pub fn triangle<T: FromStr>(text: &str) -> Result<Vec<Vec<T>>, Error> {
+    let mut rows = Vec::new();
+
+    for (line, content) in lines(text) {
+        let row: Vec<T> = parse_fields(line, whitespace_fields(content))?;
+
+        if row.len() != rows.len() + 1 {
+            return Err(Error::WrongLength {
+                position: Position { line, column: 1 },
+                expected: rows.len() + 1,
+                found: row.len(),
+            });
+        }
+
+        rows.push(row);
+    }
+
+    non_empty(rows)
+}
+
+// One number per line, as in data/79-keylog.txt
+pub fn numbers<T: FromStr>(text: &str) -> Result<Vec<T>, Error> {
+    let numbers = lines(text)
+        .map(|(line, content)| {
+            let mut fields = whitespace_fields(content).into_iter();
+            let number = parse_field(line, fields.next().unwrap())?;
+
+            match fields.next() {
+                None => Ok(number),
+                Some((column, value)) => Err(Error::Unexpected {
+                    position: Position { line, column },
+                    found: value.chars().next().unwrap(),
+                }),
+            }
+        })
+        .collect::<Result<Vec<T>, Error>>()?;
+
+    non_empty(numbers)
+}
+
+// One number per line, too big for any primitive type, as in data/13-numbers.txt
+pub fn big_integers(text: &str) -> Result<Vec<BigUint>, Error> {
+    numbers(text)
+}
+
+// Comma-separated ASCII codes, possibly spread over several lines, as in data/59-cipher.txt
+pub fn cipher(text: &str) -> Result<Vec<u8>, Error> {
+    let mut codes = Vec::new();
+
+    for (line, content) in lines(text) {
+        codes.extend(parse_fields::<u8>(line, comma_fields(content))?);
+    }
+
+    non_empty(codes)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Card {
+    // From 2 to 14, with 11 for the jack, 12 for the queen, 13 for the king and 14 for the ace
+    pub value: u8,
+    pub suit: Suit,
+}
+
+fn card(text: &str) -> Option<Card> {
+    let mut chars = text.chars();
+
+    let value = match chars.next()? {
+        digit @ '2'..='9' => digit.to_digit(10)? as u8,
+        'T' => 10,
+        'J' => 11,
+        'Q' => 12,
+        'K' => 13,
+        'A' => 14,
+        _ => return None,
+    };
+
+    let suit = match chars.next()? {
+        'C' => Suit::Clubs,
+        'D' => Suit::Diamonds,
+        'H' => Suit::Hearts,
+        'S' => Suit::Spades,
+        _ => return None,
+    };
+
+    if chars.next().is_some() {
+        return None;
+    }
+
+    Some(Card { value, suit })
+}
+
+// Lines of whitespace-separated cards such as 8C or TS, with `cards_per_line` cards each, as in
+// data/54-poker.txt
+pub fn card_hands(text: &str, cards_per_line: usize) -> Result<Vec<Vec<Card>>, Error> {
+    let mut hands = Vec::new();
+
+    for (line, content) in lines(text) {
+        let fields = whitespace_fields(content);
+
+        if fields.len() != cards_per_line {
+            return Err(Error::WrongLength {
+                position: Position { line, column: 1 },
+                expected: cards_per_line,
+                found: fields.len(),
+            });
+        }
+
+        let hand = fields
+            .into_iter()
+            .map(|(column, value)| {
+                card(value).ok_or_else(|| Error::InvalidCard {
+                    position: Position { line, column },
+                    value: value.to_owned(),
+                })
+            })
+            .collect::<Result<Vec<Card>, Error>>()?;
 
-    DataReader { reader: reader }
+        hands.push(hand);
+    }
+
+    non_empty(hands)
+}
+
+fn non_empty<T>(items: Vec<T>) -> Result<Vec<T>, Error> {
+    if items.is_empty() {
+        Err(Error::Empty)
+    } else {
+        Ok(items)
+    }
+}
+
+// The lines that aren't blank, numbered from 1
+fn lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(index, content)| (index + 1, content))
+        .filter(|(_, content)| !content.trim().is_empty())
+}
+
+// Each field between commas, trimmed, along with the column where it starts
+fn comma_fields(content: &str) -> Vec<(usize, &str)> {
+    let mut start = 0;
+
+    content
+        .split(',')
+        .map(|field| {
+            let trimmed = field.trim_start();
+            let column = start + field.len() - trimmed.len() + 1;
+            start += field.len() + 1;
+
+            (column, trimmed.trim_end())
+        })
+        .collect()
+}
+
+// Each run of non-whitespace characters, along with the column where it starts
+fn whitespace_fields(content: &str) -> Vec<(usize, &str)> {
+    let mut fields = Vec::new();
+    let mut start = None;
+
+    for (index, chr) in content
+        .char_indices()
+        .chain(iter::once((content.len(), ' ')))
+    {
+        match (start, chr.is_whitespace()) {
+            (None, false) => start = Some(index),
+            (Some(first), true) => {
+                fields.push((first + 1, &content[first..index]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    fields
+}
+
+fn parse_field<T: FromStr>(line: usize, (column, value): (usize, &str)) -> Result<T, Error> {
+    value.parse().map_err(|_| Error::InvalidNumber {
+        position: Position { line, column },
+        value: value.to_owned(),
+    })
+}
+
+fn parse_fields<T: FromStr>(line: usize, fields: Vec<(usize, &str)>) -> Result<Vec<T>, Error> {
+    fields
+        .into_iter()
+        .map(|field| parse_field(line, field))
+        .collect()
+}
+
+struct Cursor<'a> {
+    chars: Peekable<Chars<'a>>,
+    position: Position,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(text: &'a str) -> Cursor<'a> {
+        Cursor {
+            chars: text.chars().peekable(),
+            position: Position { line: 1, column: 1 },
+        }
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let chr = self.chars.next()?;
+
+        if chr == '\n' {
+            self.position.line += 1;
+            self.position.column = 1;
+        } else {
+            self.position.column += chr.len_utf8();
+        }
+
+        Some(chr)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.peek().is_some_and(|chr| chr.is_whitespace()) {
+            self.bump();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{big_integers, card_hands, cipher, matrix, numbers, triangle, words};
+    use super::{Card, Error, Position, Suit};
+    use num::BigUint;
+
+    fn at(line: usize, column: usize) -> Position {
+        Position { line, column }
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(
+            words("\"MARY\",\"PATRICIA\", \"LINDA\"\n"),
+            Ok(vec![
+                "MARY".to_owned(),
+                "PATRICIA".to_owned(),
+                "LINDA".to_owned()
+            ])
+        );
+
+        assert_eq!(words(""), Err(Error::Empty));
+        assert_eq!(
+            words("\"A\",\n\"B"),
+            Err(Error::UnexpectedEnd { position: at(2, 1) })
+        );
+        assert_eq!(
+            words("\"A\",B"),
+            Err(Error::Unexpected {
+                position: at(1, 5),
+                found: 'B'
+            })
+        );
+        assert_eq!(
+            words("\"A\" \"B\""),
+            Err(Error::Unexpected {
+                position: at(1, 5),
+                found: '"'
+            })
+        );
+    }
+
+    #[test]
+    fn test_matrix() {
+        assert_eq!(matrix("1,2\n3, 4\n\n"), Ok(vec![vec![1u32, 2], vec![3, 4]]));
+
+        assert_eq!(
+            matrix::<u32>("1,2\n3,x"),
+            Err(Error::InvalidNumber {
+                position: at(2, 3),
+                value: "x".to_owned()
+            })
+        );
+        assert_eq!(
+            matrix::<u32>("1,2\n3"),
+            Err(Error::WrongLength {
+                position: at(2, 1),
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            matrix::<u32>("1,,2"),
+            Err(Error::InvalidNumber {
+                position: at(1, 3),
+                value: "".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_triangle() {
+        assert_eq!(triangle("  1\n 2  3\n"), Ok(vec![vec![1u32], vec![2, 3]]));
+
+        assert_eq!(
+            triangle::<u32>("1\n2  x"),
+            Err(Error::InvalidNumber {
+                position: at(2, 4),
+                value: "x".to_owned()
+            })
+        );
+        assert_eq!(
+            triangle::<u32>("1\n2 3 4"),
+            Err(Error::WrongLength {
+                position: at(2, 1),
+                expected: 2,
+                found: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(numbers("319\n680\n"), Ok(vec![319u32, 680]));
+        assert_eq!(
+            big_integers("37107287533902102798797998220837590246510135740250"),
+            Ok(vec!["37107287533902102798797998220837590246510135740250"
+                .parse::<BigUint>()
+                .unwrap()])
+        );
+
+        assert_eq!(
+            numbers::<u32>("319 680"),
+            Err(Error::Unexpected {
+                position: at(1, 5),
+                found: '6'
+            })
+        );
+        assert_eq!(
+            big_integers("12\n3a4"),
+            Err(Error::InvalidNumber {
+                position: at(2, 1),
+                value: "3a4".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_cipher() {
+        assert_eq!(cipher("79,59,12\n2,79\n"), Ok(vec![79, 59, 12, 2, 79]));
+
+        assert_eq!(
+            cipher("79,256"),
+            Err(Error::InvalidNumber {
+                position: at(1, 4),
+                value: "256".to_owned()
+            })
+        );
+        assert_eq!(cipher("\n"), Err(Error::Empty));
+    }
+
+    #[test]
+    fn test_card_hands() {
+        let hands = card_hands("8C TS\nAD 2H\n", 2).unwrap();
+
+        assert_eq!(
+            hands[0],
+            vec![
+                Card {
+                    value: 8,
+                    suit: Suit::Clubs
+                },
+                Card {
+                    value: 10,
+                    suit: Suit::Spades
+                }
+            ]
+        );
+        assert_eq!(hands[1][0].value, 14);
+
+        assert_eq!(
+            card_hands("8C TS 1H", 3),
+            Err(Error::InvalidCard {
+                position: at(1, 7),
+                value: "1H".to_owned()
+            })
+        );
+        assert_eq!(
+            card_hands("8C TS\n8C", 2),
+            Err(Error::WrongLength {
+                position: at(2, 1),
+                expected: 2,
+                found: 1
+            })
+        );
+    }
 }
@@ -0,0 +1,272 @@
+use crate::roots;
+use num::{BigUint, ToPrimitive};
+use std::convert::TryFrom;
+
+// The n-th s-gonal number: triangle numbers for s = 3, squares for s = 4, and so on.
+//
+// P(s, n) = ((s - 2)n² - (s - 4)n) / 2
+//
+// Panics if the result doesn't fit in a u64; `checked_polygonal` returns None instead.
+pub fn polygonal(s: u64, n: u64) -> u64 {
+    checked_polygonal(s, n).expect("polygonal number doesn't fit in a u64")
+}
+
+// P(s, n) = n((s - 2)(n - 1) + 2) / 2, which avoids the subtraction
+pub fn checked_polygonal(s: u64, n: u64) -> Option<u64> {
+    assert!(s >= 3);
+
+    if n == 0 {
+        return Some(0);
+    }
+
+    let (s, n) = (s as u128, n as u128);
+    let doubled = ((s - 2).checked_mul(n - 1)? + 2).checked_mul(n)?;
+
+    u64::try_from(doubled / 2).ok()
+}
+
+// Returns n such that `x` is the n-th s-gonal number, if there's any.
+//
+// Solving P(s, n) = x for n gives n = ((s - 4) + √((s - 4)² + 8(s - 2)x)) / 2(s - 2)
+pub fn is_polygonal(s: u64, x: u64) -> Option<u64> {
+    assert!(s >= 3);
+
+    if x == 0 {
+        return Some(0);
+    }
+
+    let (s, x) = (s as u128, x as u128);
+    let offset = s.abs_diff(4);
+
+    // The discriminant can be as big as 2¹³¹, so big integers take over when it overflows
+    let delta = (s - 2)
+        .checked_mul(x)
+        .and_then(|product| product.checked_mul(8))
+        .and_then(|product| product.checked_add(offset * offset));
+
+    let root = match delta {
+        Some(delta) => roots::exact_sqrt(delta)?,
+        None => {
+            let delta = BigUint::from(s - 2) * x * 8u32 + offset * offset;
+            let root = delta.sqrt();
+
+            if root.clone() * &root != delta {
+                return None;
+            }
+
+            root.to_u128().unwrap()
+        }
+    };
+
+    // The root is bigger than |s - 4|, so this can't go negative
+    let numerator = root + s - 4;
+    let denominator = 2 * (s - 2);
+
+    if numerator % denominator == 0 {
+        u64::try_from(numerator / denominator).ok()
+    } else {
+        None
+    }
+}
+
+pub fn polygonal_numbers(s: u64) -> impl Iterator<Item = u64> {
+    (1..).map(move |n| polygonal(s, n))
+}
+
+// The n-th centred s-gonal number: a dot surrounded by n - 1 s-gonal layers.
+//
+// C(s, n) = s·n(n - 1)/2 + 1
+pub fn centred_polygonal(s: u64, n: u64) -> u64 {
+    assert!(s >= 1 && n >= 1);
+
+    s.checked_mul(polygonal(3, n - 1))
+        .and_then(|layers| layers.checked_add(1))
+        .expect("centred polygonal number doesn't fit in a u64")
+}
+
+pub fn is_centred_polygonal(s: u64, x: u64) -> Option<u64> {
+    assert!(s >= 1);
+
+    if x == 0 || !(x - 1).is_multiple_of(s) {
+        return None;
+    }
+
+    is_polygonal(3, (x - 1) / s).map(|n| n + 1)
+}
+
+pub fn centred_polygonal_numbers(s: u64) -> impl Iterator<Item = u64> {
+    (1..).map(move |n| centred_polygonal(s, n))
+}
+
+// The n-th s-gonal pyramidal number: the sum of the first n s-gonal numbers.
+//
+// Pyr(s, n) = n(n + 1)((s - 2)n - (s - 5)) / 6
+//
+// Panics if the result doesn't fit in a u64; `checked_pyramidal` returns None instead.
+pub fn pyramidal(s: u64, n: u64) -> u64 {
+    checked_pyramidal(s, n).expect("pyramidal number doesn't fit in a u64")
+}
+
+pub fn checked_pyramidal(s: u64, n: u64) -> Option<u64> {
+    assert!(s >= 3);
+
+    if n == 0 {
+        return Some(0);
+    }
+
+    let (s, n) = (s as u128, n as u128);
+    let last_factor = (s - 2).checked_mul(n - 1)? + 3;
+    let product = (n * (n + 1)).checked_mul(last_factor)?;
+
+    u64::try_from(product / 6).ok()
+}
+
+// Binary searches for n, treating pyramidal numbers that overflow as larger than any `x`
+pub fn is_pyramidal(s: u64, x: u64) -> Option<u64> {
+    let below = |n| checked_pyramidal(s, n).is_some_and(|pyramidal| pyramidal < x);
+
+    let mut low = 0;
+    let mut high = 1;
+
+    while below(high) {
+        high *= 2;
+    }
+
+    while low < high {
+        let middle = low + (high - low) / 2;
+
+        if below(middle) {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+
+    if checked_pyramidal(s, low) == Some(x) {
+        Some(low)
+    } else {
+        None
+    }
+}
+
+pub fn pyramidal_numbers(s: u64) -> impl Iterator<Item = u64> {
+    (1..).map(move |n| pyramidal(s, n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{centred_polygonal_numbers, checked_polygonal, checked_pyramidal};
+    use super::{is_centred_polygonal, is_polygonal, is_pyramidal};
+    use super::{polygonal, polygonal_numbers, pyramidal, pyramidal_numbers};
+
+    #[test]
+    fn test_polygonal_numbers() {
+        let first = |s| polygonal_numbers(s).take(5).collect::<Vec<_>>();
+
+        assert_eq!(first(3), vec![1, 3, 6, 10, 15]);
+        assert_eq!(first(4), vec![1, 4, 9, 16, 25]);
+        assert_eq!(first(5), vec![1, 5, 12, 22, 35]);
+        assert_eq!(first(6), vec![1, 6, 15, 28, 45]);
+        assert_eq!(first(7), vec![1, 7, 18, 34, 55]);
+        assert_eq!(first(8), vec![1, 8, 21, 40, 65]);
+    }
+
+    #[test]
+    fn test_is_polygonal() {
+        let triangles = [1, 3, 6, 10, 15, 21, 28, 36, 45, 55];
+
+        for (index, &triangle) in triangles.iter().enumerate() {
+            assert_eq!(is_polygonal(3, triangle), Some(index as u64 + 1));
+        }
+
+        for s in 3..20 {
+            let mut numbers = polygonal_numbers(s).take(200).peekable();
+
+            for x in 1..=polygonal(s, 200) {
+                let expected = if numbers.peek() == Some(&x) {
+                    numbers.next();
+                    Some((1..).find(|&n| polygonal(s, n) == x).unwrap())
+                } else {
+                    None
+                };
+
+                assert_eq!(is_polygonal(s, x), expected);
+            }
+        }
+
+        assert_eq!(is_polygonal(3, 40755), Some(285));
+        assert_eq!(is_polygonal(5, 40755), Some(165));
+        assert_eq!(is_polygonal(6, 40755), Some(143));
+        assert_eq!(is_polygonal(5, 5482660), Some(1912));
+    }
+
+    #[test]
+    fn test_is_polygonal_near_the_limits() {
+        // P(s, 2) = s for every s
+        assert_eq!(is_polygonal(u64::MAX, u64::MAX), Some(2));
+        assert_eq!(is_polygonal(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(is_polygonal(u64::MAX, 1), Some(1));
+        assert_eq!(is_polygonal(1 << 62, 1 << 62), Some(2));
+
+        let s = 1 << 62;
+        assert_eq!(is_polygonal(s, polygonal(s, 3)), Some(3));
+        assert_eq!(is_polygonal(s, polygonal(s, 3) + 1), None);
+
+        let last_triangle = 6_074_000_999;
+        assert_eq!(
+            is_polygonal(3, polygonal(3, last_triangle)),
+            Some(last_triangle)
+        );
+        assert_eq!(is_polygonal(4, u64::MAX), None);
+        assert_eq!(
+            is_polygonal(4, (u32::MAX as u64).pow(2)),
+            Some(u32::MAX as u64)
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        // T(n) = n(n + 1)/2 first overflows a u64 just past n = 2³²·√2
+        let last = 6_074_000_999;
+        let expected = last as u128 * (last as u128 + 1) / 2;
+        assert_eq!(checked_polygonal(3, last), Some(expected as u64));
+        assert_eq!(checked_polygonal(3, last + 1), None);
+        assert_eq!(checked_polygonal(3, u64::MAX), None);
+        assert_eq!(checked_polygonal(u64::MAX, u64::MAX), None);
+        assert_eq!(checked_polygonal(u64::MAX, 1), Some(1));
+
+        assert_eq!(checked_pyramidal(3, u64::MAX), None);
+        assert_eq!(is_pyramidal(3, u64::MAX), None);
+
+        // The largest square pyramidal number fitting in a u64
+        let largest = 18_446_735_571_075_162_805;
+        assert_eq!(pyramidal(4, 3_810_777), largest);
+        assert_eq!(checked_pyramidal(4, 3_810_778), None);
+        assert_eq!(is_pyramidal(4, largest), Some(3_810_777));
+        assert_eq!(is_pyramidal(4, largest + 1), None);
+    }
+
+    #[test]
+    fn test_centred_polygonal_numbers() {
+        let hexagonals: Vec<_> = centred_polygonal_numbers(6).take(5).collect();
+        assert_eq!(hexagonals, vec![1, 7, 19, 37, 61]);
+
+        assert_eq!(is_centred_polygonal(6, 37), Some(4));
+        assert_eq!(is_centred_polygonal(6, 38), None);
+        assert_eq!(is_centred_polygonal(4, 1), Some(1));
+        assert_eq!(is_centred_polygonal(1, 1), Some(1));
+    }
+
+    #[test]
+    fn test_pyramidal_numbers() {
+        let tetrahedral: Vec<_> = pyramidal_numbers(3).take(5).collect();
+        assert_eq!(tetrahedral, vec![1, 4, 10, 20, 35]);
+
+        let square: Vec<_> = pyramidal_numbers(4).take(5).collect();
+        assert_eq!(square, vec![1, 5, 14, 30, 55]);
+
+        assert_eq!(is_pyramidal(4, 4900), Some(24));
+        assert_eq!(is_pyramidal(4, 4901), None);
+        assert_eq!(is_pyramidal(3, 0), Some(0));
+    }
+}
@@ -0,0 +1,155 @@
+use crate::pythagorean_triplets::{self, Limit};
+use crate::roots::isqrt;
+
+// How many integer sided right triangles have each perimeter, indexed by perimeter, up to
+// `max_perimeter`. Each primitive triangle marks all multiples of its perimeter, like a sieve.
+pub fn right_triangles_by_perimeter(max_perimeter: usize) -> Vec<u32> {
+    let mut counts = vec![0; max_perimeter + 1];
+
+    for (a, b, c) in pythagorean_triplets::primitive_up_to(Limit::Perimeter(max_perimeter)) {
+        let perimeter = a + b + c;
+
+        for multiple in (perimeter..=max_perimeter).step_by(perimeter) {
+            counts[multiple] += 1;
+        }
+    }
+
+    counts
+}
+
+// Counts cuboids, ignoring rotations, whose sides are at most M and whose shortest route along the
+// surface between opposite corners has integer length. Yields (M, count) for M = 1, 2, 3...
+//
+// For a cuboid a×b×c with a ≥ b ≥ c, the shortest route is √(a² + (b + c)²). Each M only adds
+// cuboids whose longest side is M, which are found from the triplets that have M as a leg.
+pub struct CuboidRoutes {
+    m: usize,
+    count: u64,
+}
+
+impl Iterator for CuboidRoutes {
+    type Item = (usize, u64);
+
+    fn next(&mut self) -> Option<(usize, u64)> {
+        self.m += 1;
+        let a = self.m;
+
+        for (x, y, _) in pythagorean_triplets::with_leg(a) {
+            let sum = if x == a { y } else { x };
+
+            // Pairs c ≤ b ≤ a with b + c = sum
+            if sum <= 2 * a {
+                let smallest_c = if sum > a { sum - a } else { 1 };
+                let biggest_c = sum / 2;
+
+                if biggest_c >= smallest_c {
+                    self.count += (biggest_c - smallest_c + 1) as u64;
+                }
+            }
+        }
+
+        Some((self.m, self.count))
+    }
+}
+
+pub fn cuboid_routes() -> CuboidRoutes {
+    CuboidRoutes { m: 0, count: 0 }
+}
+
+// Number of rectangles of any size and position in a width × height grid
+pub fn rectangles_in_grid(width: u64, height: u64) -> u64 {
+    width * (width + 1) / 2 * (height * (height + 1) / 2)
+}
+
+// The (width, height, rectangle count) of the grid whose rectangle count is closest to `target`,
+// with width ≤ height. Ties go to the narrowest grid.
+pub fn closest_rectangle_grid(target: u64) -> (u64, u64, u64) {
+    let triangular = |n: u64| n * (n + 1) / 2;
+    let distance = |count: u64| count.max(target) - count.min(target);
+
+    let mut best = (1, 1, 1);
+
+    // Past this point even a square grid has more than twice the target, which is further away
+    // than the 1×1 grid
+    for width in (1..).take_while(|&width| triangular(width).pow(2) <= 2 * target.max(1)) {
+        let per_row = triangular(width);
+
+        // The biggest height whose triangular number doesn't go over target / per_row
+        let quotient = (target / per_row) as u128;
+        let height = ((isqrt(8 * quotient + 1) - 1) / 2) as u64;
+
+        for height in height.max(width)..=height.max(width) + 1 {
+            let count = rectangles_in_grid(width, height);
+
+            if distance(count) < distance(best.2) {
+                best = (width, height, count);
+            }
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::right_triangles_by_perimeter;
+    use super::{closest_rectangle_grid, cuboid_routes, rectangles_in_grid};
+
+    #[test]
+    fn test_right_triangles_by_perimeter() {
+        let counts = right_triangles_by_perimeter(1000);
+
+        assert_eq!(counts[12], 1);
+        assert_eq!(counts[20], 0);
+        assert_eq!(counts[120], 3);
+        assert_eq!((0..=1000).max_by_key(|&p| counts[p]), Some(840));
+    }
+
+    #[test]
+    fn test_cuboid_routes() {
+        let routes: Vec<_> = cuboid_routes().take(100).collect();
+
+        assert_eq!(routes[98], (99, 1975));
+        assert_eq!(routes[99], (100, 2060));
+
+        // Brute force for small sizes
+        for &(m, count) in routes.iter().take(30) {
+            let mut expected = 0;
+
+            for a in 1..=m {
+                for b in 1..=a {
+                    for c in 1..=b {
+                        let square = a * a + (b + c) * (b + c);
+                        let root = (square as f64).sqrt() as usize;
+
+                        if root * root == square {
+                            expected += 1;
+                        }
+                    }
+                }
+            }
+
+            assert_eq!(count, expected);
+        }
+    }
+
+    #[test]
+    fn test_rectangles() {
+        assert_eq!(rectangles_in_grid(3, 2), 18);
+        assert_eq!(closest_rectangle_grid(18), (2, 3, 18));
+        assert_eq!(closest_rectangle_grid(1), (1, 1, 1));
+
+        for target in 1..500 {
+            let (_, _, count) = closest_rectangle_grid(target);
+            let distance = |count: u64| (count as i64 - target as i64).abs();
+
+            let best = (1..50)
+                .flat_map(|width| (1..50).map(move |height| rectangles_in_grid(width, height)))
+                .map(distance)
+                .min()
+                .unwrap();
+
+            assert_eq!(distance(count), best, "target {}", target);
+        }
+    }
+}
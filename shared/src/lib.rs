@@ -1,17 +1,29 @@
 pub use crate::integer_extensions::IntegerExtensions;
-pub use crate::permutations::Permutations;
+pub use crate::permutations::{LendingPermutations, Permutations};
 
 pub mod a_star;
+pub mod binomial;
 pub mod combinations;
 pub mod continued_fraction;
+pub mod data;
 pub mod data_reader;
 mod digit_count;
 pub mod digits;
+pub mod figurate;
+pub mod geometry;
 pub mod integer_extensions;
+pub mod modular;
 pub mod pandigital;
+pub mod partitions;
+pub mod pell;
 pub mod permutations;
 pub mod primes;
 pub mod pythagorean_triplets;
+pub mod rationals;
+pub mod recurring;
+pub mod roots;
 pub mod sieve;
+#[cfg(test)]
+mod test_random;
 pub mod totient;
 pub mod triangle;
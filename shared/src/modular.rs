@@ -0,0 +1,60 @@
+// a + b mod m, for a, b < m, without overflowing even when m is close to u64::MAX
+pub fn add_mod(a: u64, b: u64, m: u64) -> u64 {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+// a - b mod m, for a, b < m
+pub fn sub_mod(a: u64, b: u64, m: u64) -> u64 {
+    if a >= b {
+        a - b
+    } else {
+        a + (m - b)
+    }
+}
+
+// a·b mod m, going through u128 so the product can't overflow
+pub fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+
+// `base` raised to `exponent`, mod m, by repeated squaring
+pub fn pow_mod(mut base: u64, mut exponent: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    base %= m;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+
+        base = mul_mod(base, base, m);
+        exponent >>= 1;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{add_mod, mul_mod, pow_mod, sub_mod};
+
+    #[test]
+    fn test_large_operands() {
+        let m = u64::MAX - 1;
+        assert_eq!(add_mod(m - 1, m - 1, m), m - 2);
+        assert_eq!(add_mod(3, 4, 10), 7);
+        assert_eq!(add_mod(6, 4, 10), 0);
+        assert_eq!(sub_mod(1, m - 1, m), 2);
+        assert_eq!(sub_mod(7, 3, 10), 4);
+
+        assert_eq!(mul_mod(u64::MAX, u64::MAX, u64::MAX - 1), 1);
+        assert_eq!(pow_mod(2, 64, u64::MAX), 1);
+        assert_eq!(pow_mod(3, 1_000_000_006, 1_000_000_007), 1);
+        assert_eq!(pow_mod(5, 0, 1), 0);
+        assert_eq!(pow_mod(u64::MAX, 2, 10), 5 * 5 % 10);
+    }
+}
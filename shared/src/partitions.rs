@@ -0,0 +1,334 @@
+use crate::modular::{add_mod, sub_mod};
+use crate::sieve::{self, Sieve};
+use num::{BigUint, One, Zero};
+use std::ops::AddAssign;
+
+// Euler's pentagonal number theorem:
+//
+// p(n) = Σ (-1)^(k + 1) [p(n - k(3k - 1)/2) + p(n - k(3k + 1)/2)], for k = 1, 2, ...
+//
+// Calls `f(sign, m)` with each p(m) that takes part in the sum for p(n).
+fn pentagonal_terms(n: usize, mut f: impl FnMut(bool, usize)) {
+    for k in 1.. {
+        let first = k * (3 * k - 1) / 2;
+
+        if first > n {
+            break;
+        }
+
+        let positive = k % 2 == 1;
+        f(positive, n - first);
+
+        let second = first + k;
+        if second <= n {
+            f(positive, n - second);
+        }
+    }
+}
+
+// p(0), p(1), ..., p(n)
+pub fn up_to(n: usize) -> Vec<BigUint> {
+    let mut counts: Vec<BigUint> = Vec::with_capacity(n + 1);
+    counts.push(BigUint::one());
+
+    for current in 1..=n {
+        let mut positive = BigUint::zero();
+        let mut negative = BigUint::zero();
+
+        pentagonal_terms(current, |is_positive, m| {
+            if is_positive {
+                positive += &counts[m];
+            } else {
+                negative += &counts[m];
+            }
+        });
+
+        counts.push(positive - negative);
+    }
+
+    counts
+}
+
+pub fn count(n: usize) -> BigUint {
+    up_to(n).pop().unwrap()
+}
+
+// p(0), p(1), ... modulo m, without an upper bound on n
+pub struct PartitionsMod {
+    modulus: u64,
+    counts: Vec<u64>,
+}
+
+pub fn modulo(modulus: u64) -> PartitionsMod {
+    assert!(modulus > 0);
+
+    PartitionsMod {
+        modulus,
+        counts: Vec::new(),
+    }
+}
+
+impl Iterator for PartitionsMod {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let modulus = self.modulus;
+        let n = self.counts.len();
+
+        let next = if n == 0 {
+            1 % modulus
+        } else {
+            let counts = &self.counts;
+            let mut sum = 0;
+
+            pentagonal_terms(n, |is_positive, m| {
+                sum = if is_positive {
+                    add_mod(sum, counts[m], modulus)
+                } else {
+                    sub_mod(sum, counts[m], modulus)
+                };
+            });
+
+            sum
+        };
+
+        self.counts.push(next);
+        Some(next)
+    }
+}
+
+pub fn up_to_mod(n: usize, modulus: u64) -> Vec<u64> {
+    modulo(modulus).take(n + 1).collect()
+}
+
+// Number of ways of writing 0, 1, ..., n as a sum of elements of `parts`, where each part may be
+// used any number of times (e.g. making change with a set of coins).
+pub fn into_parts<T>(n: usize, parts: &[usize]) -> Vec<T>
+where
+    T: Zero + One + Clone,
+    for<'a> T: AddAssign<&'a T>,
+{
+    let mut ways = vec![T::zero(); n + 1];
+    ways[0] = T::one();
+
+    for &part in parts {
+        assert!(part > 0);
+
+        for target in part..=n {
+            let (before, after) = ways.split_at_mut(target);
+            after[0] += &before[target - part];
+        }
+    }
+
+    ways
+}
+
+pub fn into_primes<T>(n: usize) -> Vec<T>
+where
+    T: Zero + One + Clone,
+    for<'a> T: AddAssign<&'a T>,
+{
+    let primes_sieve: Sieve<usize> = sieve::new();
+    let primes: Vec<usize> = primes_sieve.take_while(|&prime| prime <= n).collect();
+
+    into_parts(n, &primes)
+}
+
+// Same as `into_parts`, but each part may be used at most once.
+pub fn into_distinct_parts<T>(n: usize, parts: &[usize]) -> Vec<T>
+where
+    T: Zero + One + Clone,
+    for<'a> T: AddAssign<&'a T>,
+{
+    let mut ways = vec![T::zero(); n + 1];
+    ways[0] = T::one();
+
+    for &part in parts {
+        assert!(part > 0);
+
+        // Going downwards means ways[target - part] doesn't include `part` yet
+        for target in (part..=n).rev() {
+            let (before, after) = ways.split_at_mut(target);
+            after[0] += &before[target - part];
+        }
+    }
+
+    ways
+}
+
+// The actual partitions of `n` into elements of `parts`, each one in non-increasing order. The
+// partitions themselves are yielded in reverse lexicographic order.
+pub struct Enumeration {
+    parts: Vec<usize>,
+    chosen: Vec<usize>,
+    remaining: usize,
+    first: bool,
+}
+
+pub fn enumerate(n: usize, parts: &[usize]) -> Enumeration {
+    let mut parts = parts.to_vec();
+    assert!(parts.iter().all(|&part| part > 0));
+
+    parts.sort_unstable_by(|a, b| b.cmp(a));
+    parts.dedup();
+
+    Enumeration {
+        parts,
+        chosen: Vec::new(),
+        remaining: n,
+        first: true,
+    }
+}
+
+impl Enumeration {
+    // Index of the biggest part, starting from `from`, that still fits in what's remaining
+    fn fitting_part(&self, from: usize) -> Option<usize> {
+        (from..self.parts.len()).find(|&index| self.parts[index] <= self.remaining)
+    }
+
+    fn choose(&mut self, index: usize) {
+        self.chosen.push(index);
+        self.remaining -= self.parts[index];
+    }
+
+    // Greedily completes the current partition, using parts no bigger than `parts[from]`
+    fn descend(&mut self, from: usize) -> bool {
+        while self.remaining > 0 {
+            match self.fitting_part(from) {
+                Some(index) => self.choose(index),
+                None => return false,
+            }
+        }
+
+        true
+    }
+
+    fn current(&self) -> Vec<usize> {
+        self.chosen.iter().map(|&index| self.parts[index]).collect()
+    }
+}
+
+impl Iterator for Enumeration {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        if self.first {
+            self.first = false;
+
+            if self.descend(0) {
+                return Some(self.current());
+            }
+        }
+
+        loop {
+            let last = self.chosen.pop()?;
+            self.remaining += self.parts[last];
+
+            if let Some(index) = self.fitting_part(last + 1) {
+                self.choose(index);
+
+                if self.descend(index) {
+                    return Some(self.current());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count, enumerate, into_distinct_parts, into_parts, into_primes, modulo};
+    use super::{up_to, up_to_mod};
+    use num::{BigUint, ToPrimitive};
+
+    #[test]
+    fn test_partition_numbers() {
+        let counts: Vec<_> = up_to(12).iter().map(|n| n.to_u64().unwrap()).collect();
+        assert_eq!(counts, vec![1, 1, 2, 3, 5, 7, 11, 15, 22, 30, 42, 56, 77]);
+
+        assert_eq!(count(100), BigUint::from(190_569_292u32));
+        assert_eq!(count(1000).to_string(), "24061467864032622473692149727991");
+    }
+
+    #[test]
+    fn test_partition_numbers_mod() {
+        let exact = up_to(300);
+        let reduced = up_to_mod(300, 1_000_000);
+
+        for (exact, reduced) in exact.iter().zip(reduced) {
+            assert_eq!(exact % BigUint::from(1_000_000u32), BigUint::from(reduced));
+        }
+
+        assert_eq!(modulo(1).take(3).collect::<Vec<_>>(), vec![0, 0, 0]);
+
+        // With a modulus above 2⁶³, two residues can add up to more than u64::MAX, so the terms of
+        // the recurrence have to be combined without forming that sum first
+        let modulus = u64::MAX - 58;
+        let exact = up_to(1000);
+        let reduced = up_to_mod(1000, modulus);
+
+        for (exact, reduced) in exact.iter().zip(reduced) {
+            assert_eq!(exact % BigUint::from(modulus), BigUint::from(reduced));
+        }
+    }
+
+    #[test]
+    fn test_coins() {
+        let coins = [1, 2, 5, 10, 20, 50, 100, 200];
+        let ways: Vec<u64> = into_parts(200, &coins);
+
+        assert_eq!(ways[200], 73682);
+        assert_eq!(into_parts::<u32>(5, &[2, 3])[..], [1, 0, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_prime_parts() {
+        let ways: Vec<u32> = into_primes(10);
+        assert_eq!(ways[10], 5);
+
+        let ways: Vec<u32> = into_primes(100);
+        assert_eq!(ways.iter().position(|&w| w > 5000), Some(71));
+    }
+
+    #[test]
+    fn test_distinct_parts() {
+        let parts: Vec<_> = (1..=10).collect();
+        let ways: Vec<u32> = into_distinct_parts(10, &parts);
+
+        assert_eq!(ways, vec![1, 1, 1, 2, 2, 3, 4, 5, 6, 8, 10]);
+    }
+
+    #[test]
+    fn test_enumeration() {
+        let partitions: Vec<_> = enumerate(5, &[1, 2, 3, 4, 5]).collect();
+
+        assert_eq!(
+            partitions,
+            vec![
+                vec![5],
+                vec![4, 1],
+                vec![3, 2],
+                vec![3, 1, 1],
+                vec![2, 2, 1],
+                vec![2, 1, 1, 1],
+                vec![1, 1, 1, 1, 1],
+            ]
+        );
+
+        let ten_in_primes: Vec<_> = enumerate(10, &[2, 3, 5, 7]).collect();
+        assert_eq!(
+            ten_in_primes,
+            vec![
+                vec![7, 3],
+                vec![5, 5],
+                vec![5, 3, 2],
+                vec![3, 3, 2, 2],
+                vec![2, 2, 2, 2, 2],
+            ]
+        );
+
+        assert_eq!(enumerate(0, &[1]).collect::<Vec<_>>(), vec![vec![]]);
+        assert_eq!(enumerate(7, &[2, 4]).count(), 0);
+        assert_eq!(enumerate(30, &(1..=30).collect::<Vec<_>>()).count(), 5604);
+    }
+}
@@ -0,0 +1,444 @@
+use crate::continued_fraction::{self, expand_surd, Expansion};
+use crate::roots;
+use num::bigint::Sign;
+use num::{BigInt, BigUint, One, Signed, Zero};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Solution {
+    pub x: BigUint,
+    pub y: BigUint,
+}
+
+impl Solution {
+    fn new(x: impl Into<BigUint>, y: impl Into<BigUint>) -> Solution {
+        Solution {
+            x: x.into(),
+            y: y.into(),
+        }
+    }
+
+    // (x + y√d)(other.x + other.y√d)
+    fn compose(&self, other: &Solution, d: u64) -> Solution {
+        let d = BigUint::from(d);
+
+        Solution {
+            x: &self.x * &other.x + d * &self.y * &other.y,
+            y: &self.x * &other.y + &self.y * &other.x,
+        }
+    }
+}
+
+fn period_len(d: u64) -> Option<usize> {
+    match expand_surd(0, d, 1) {
+        Expansion::Finite(_) => None,
+        Expansion::Periodic { period, .. } => Some(period.len()),
+    }
+}
+
+fn convergent(d: u64, index: usize) -> Solution {
+    let pair = continued_fraction::square_root(d)
+        .convergents::<BigUint>()
+        .nth(index)
+        .unwrap();
+
+    Solution::new(pair.numerator, pair.denominator)
+}
+
+// Smallest positive solution of x² - dy² = 1, or None if d is a perfect square.
+//
+// It's always one of the convergents of √d: the one just before the end of the first period if the
+// period has even length, or of the second period otherwise.
+pub fn fundamental(d: u64) -> Option<Solution> {
+    let len = period_len(d)?;
+
+    if len % 2 == 0 {
+        Some(convergent(d, len - 1))
+    } else {
+        Some(convergent(d, 2 * len - 1))
+    }
+}
+
+// Smallest positive solution of x² - dy² = -1, which exists only if the period of √d is odd.
+pub fn fundamental_negative(d: u64) -> Option<Solution> {
+    let len = period_len(d)?;
+
+    if len % 2 == 1 {
+        Some(convergent(d, len - 1))
+    } else {
+        None
+    }
+}
+
+// Every positive solution of x² - dy² = 1 (or -1), in increasing order
+pub struct Solutions {
+    d: u64,
+    step: Solution,
+    current: Solution,
+}
+
+impl Iterator for Solutions {
+    type Item = Solution;
+
+    fn next(&mut self) -> Option<Solution> {
+        let next = self.current.compose(&self.step, self.d);
+        Some(std::mem::replace(&mut self.current, next))
+    }
+}
+
+pub fn solutions(d: u64) -> Option<Solutions> {
+    let fundamental = fundamental(d)?;
+
+    Some(Solutions {
+        d,
+        step: fundamental.clone(),
+        current: fundamental,
+    })
+}
+
+// The solutions of x² - dy² = -1 are the odd powers of the fundamental one.
+pub fn negative_solutions(d: u64) -> Option<Solutions> {
+    let fundamental = fundamental_negative(d)?;
+
+    Some(Solutions {
+        d,
+        step: fundamental.compose(&fundamental, d),
+        current: fundamental,
+    })
+}
+
+// One solution of x² - dy² = n from each class of solutions, found with the
+// Lagrange-Matthews-Mollin (LMM) algorithm. Every other solution can be obtained by multiplying
+// one of these by a solution of x² - dy² = 1.
+//
+// K. Matthews, "The Diophantine Equation x² − Dy² = N, D > 0", Expositiones Math. 18 (2000)
+//
+// n can be anything but 0 and i64::MIN, whose absolute value doesn't fit in an i64.
+pub fn class_representatives(d: u64, n: i64) -> Vec<(BigInt, BigInt)> {
+    assert!(n != 0 && n != i64::MIN);
+
+    assert!(
+        roots::exact_sqrt(d as u128).is_none(),
+        "d can't be a perfect square"
+    );
+
+    let negative_unit = fundamental_negative(d);
+    let mut representatives = Vec::new();
+
+    // Non-primitive solutions are f times a primitive solution of x² - dy² = n/f²
+    let fits = |f: &i64| f.checked_mul(*f).is_some_and(|square| square <= n.abs());
+
+    for f in (1..).take_while(fits) {
+        if n % (f * f) != 0 {
+            continue;
+        }
+
+        let m = n / (f * f);
+        let f = BigInt::from(f);
+
+        for (x, y) in primitive_representatives(d, m, negative_unit.as_ref()) {
+            representatives.push((x * &f, y * &f));
+        }
+    }
+
+    representatives
+}
+
+fn primitive_representatives(
+    d: u64,
+    m: i64,
+    negative_unit: Option<&Solution>,
+) -> Vec<(BigInt, BigInt)> {
+    let to_int = |n: &BigUint| BigInt::from_biguint(Sign::Plus, n.clone());
+
+    match m {
+        1 => return vec![(BigInt::one(), BigInt::zero())],
+        -1 => {
+            return negative_unit
+                .map(|solution| (to_int(&solution.x), to_int(&solution.y)))
+                .into_iter()
+                .collect();
+        }
+
+        _ => {}
+    }
+
+    let modulus = m.abs();
+    let mut representatives = Vec::new();
+
+    // -|m|/2 < z ≤ |m|/2, with z² ≡ d (mod |m|)
+    for z in (-(modulus - 1) / 2)..=(modulus / 2) {
+        let z_squared = z as i128 * z as i128;
+
+        if (z_squared - d as i128).rem_euclid(modulus as i128) != 0 {
+            continue;
+        }
+
+        let (g, b, value) = match first_unit_convergent(d, z, modulus) {
+            Some(found) => found,
+            None => continue,
+        };
+
+        if value == m {
+            representatives.push((g, b));
+        } else if let Some(negative_unit) = negative_unit {
+            // Multiplying by a solution of x² - dy² = -1 flips the sign of the right hand side
+            let (t, u) = (to_int(&negative_unit.x), to_int(&negative_unit.y));
+            let d = BigInt::from(d);
+
+            representatives.push((&g * &t + d * &b * &u, &g * &u + &b * &t));
+        }
+    }
+
+    representatives
+}
+
+// Expands (z + √d)/|m| until the first Qᵢ = ±1 (i ≥ 1), returning (G, B, G² - dB²).
+//
+// P and Q are kept in i128s, since P² can be as big as (|m| + √d)².
+fn first_unit_convergent(d: u64, z: i64, modulus: i64) -> Option<(BigInt, BigInt, i64)> {
+    let root = roots::isqrt(d as u128) as i128;
+
+    let (p0, q0) = (z as i128, modulus as i128);
+    let (mut p, mut q) = (p0, q0);
+
+    // A₋₁/B₋₁ = 1/0 and A₋₂/B₋₂ = 0/1
+    let (mut a_prev, mut a_pprev) = (BigInt::one(), BigInt::zero());
+    let (mut b_prev, mut b_pprev) = (BigInt::zero(), BigInt::one());
+
+    let mut seen = std::collections::HashSet::new();
+
+    for i in 1.. {
+        if !seen.insert((p, q)) {
+            return None;
+        }
+
+        let term = if q > 0 {
+            (p + root).div_euclid(q)
+        } else {
+            (p + root + 1).div_euclid(q)
+        };
+
+        let a = BigInt::from(term) * &a_prev + &a_pprev;
+        let b = BigInt::from(term) * &b_prev + &b_pprev;
+
+        p = term * q - p;
+        q = (d as i128 - p * p) / q;
+
+        if q.abs() == 1 {
+            let g = BigInt::from(q0) * &a - BigInt::from(p0) * &b;
+            let sign = if i % 2 == 0 { 1 } else { -1 };
+
+            return Some((g, b, sign * q as i64 * modulus));
+        }
+
+        a_pprev = std::mem::replace(&mut a_prev, a);
+        b_pprev = std::mem::replace(&mut b_prev, b);
+    }
+
+    unreachable!()
+}
+
+// Every non-negative solution of x² - dy² = n, ordered by y.
+pub struct GeneralSolutions {
+    d: u64,
+    unit: Solution,
+    queue: BinaryHeap<Reverse<(BigUint, BigUint)>>,
+    last: Option<Solution>,
+}
+
+impl Iterator for GeneralSolutions {
+    type Item = Solution;
+
+    fn next(&mut self) -> Option<Solution> {
+        loop {
+            let Reverse((y, x)) = self.queue.pop()?;
+            let solution = Solution { x, y };
+
+            // Each chain only ever grows, so the next one can be queued right away
+            let next = solution.compose(&self.unit, self.d);
+            self.queue.push(Reverse((next.y, next.x)));
+
+            if self.last.as_ref() == Some(&solution) {
+                continue;
+            }
+
+            self.last = Some(solution.clone());
+            return Some(solution);
+        }
+    }
+}
+
+pub fn general_solutions(d: u64, n: i64) -> GeneralSolutions {
+    let unit = fundamental(d).expect("d can't be a perfect square");
+    let mut queue = BinaryHeap::new();
+
+    for (x, y) in class_representatives(d, n) {
+        let smallest = descend(d, &unit, x, y);
+        let other = absolute(divide_by_unit(d, &unit, &to_signed(&smallest)));
+
+        for start in [smallest, other].iter().cloned() {
+            queue.push(Reverse((start.y, start.x)));
+        }
+    }
+
+    GeneralSolutions {
+        d,
+        unit,
+        queue,
+        last: None,
+    }
+}
+
+fn to_signed(solution: &Solution) -> (BigInt, BigInt) {
+    (
+        BigInt::from_biguint(Sign::Plus, solution.x.clone()),
+        BigInt::from_biguint(Sign::Plus, solution.y.clone()),
+    )
+}
+
+fn absolute((x, y): (BigInt, BigInt)) -> Solution {
+    Solution {
+        x: x.abs().to_biguint().unwrap(),
+        y: y.abs().to_biguint().unwrap(),
+    }
+}
+
+// (x + y√d) / (unit.x + unit.y√d) = (x + y√d)(unit.x - unit.y√d)
+fn divide_by_unit(d: u64, unit: &Solution, (x, y): &(BigInt, BigInt)) -> (BigInt, BigInt) {
+    let (t, u) = to_signed(unit);
+    let d = BigInt::from(d);
+
+    (x * &t - d * y * &u, y * &t - x * &u)
+}
+
+// Divides by the unit for as long as that keeps making the solution smaller
+fn descend(d: u64, unit: &Solution, x: BigInt, y: BigInt) -> Solution {
+    let mut current = absolute((x, y));
+
+    loop {
+        let smaller = absolute(divide_by_unit(d, unit, &to_signed(&current)));
+
+        if smaller.y >= current.y {
+            return current;
+        }
+
+        current = smaller;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fundamental, fundamental_negative, general_solutions, negative_solutions};
+    use super::{solutions, Solution};
+    use crate::roots;
+    use num::BigUint;
+
+    fn solution(x: u64, y: u64) -> Solution {
+        Solution::new(x, y)
+    }
+
+    #[test]
+    fn test_fundamental_solutions() {
+        assert_eq!(fundamental(2), Some(solution(3, 2)));
+        assert_eq!(fundamental(3), Some(solution(2, 1)));
+        assert_eq!(fundamental(5), Some(solution(9, 4)));
+        assert_eq!(fundamental(6), Some(solution(5, 2)));
+        assert_eq!(fundamental(7), Some(solution(8, 3)));
+        assert_eq!(fundamental(13), Some(solution(649, 180)));
+        assert_eq!(fundamental(9), None);
+
+        let x: BigUint = "16421658242965910275055840472270471049".parse().unwrap();
+        assert_eq!(fundamental(661).unwrap().x, x);
+    }
+
+    #[test]
+    fn test_negative_solutions() {
+        assert_eq!(fundamental_negative(2), Some(solution(1, 1)));
+        assert_eq!(fundamental_negative(13), Some(solution(18, 5)));
+        assert_eq!(fundamental_negative(3), None);
+
+        let first: Vec<_> = negative_solutions(2).unwrap().take(3).collect();
+        assert_eq!(
+            first,
+            vec![solution(1, 1), solution(7, 5), solution(41, 29)]
+        );
+    }
+
+    #[test]
+    fn test_solution_iterator() {
+        let first: Vec<_> = solutions(2).unwrap().take(4).collect();
+
+        assert_eq!(
+            first,
+            vec![
+                solution(3, 2),
+                solution(17, 12),
+                solution(99, 70),
+                solution(577, 408)
+            ]
+        );
+    }
+
+    fn brute_force(d: u64, n: i64, max_y: u64) -> Vec<Solution> {
+        (0..=max_y)
+            .filter_map(|y| {
+                let square = n + (d * y * y) as i64;
+
+                if square < 0 {
+                    return None;
+                }
+
+                roots::exact_sqrt(square as u128).map(|x| solution(x as u64, y))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_general_solutions() {
+        for d in 2..30 {
+            if roots::exact_sqrt(d as u128).is_some() {
+                continue;
+            }
+
+            for n in -40..=40 {
+                if n == 0 {
+                    continue;
+                }
+
+                let max_y = 5_000;
+                let expected = brute_force(d, n, max_y);
+
+                let found: Vec<_> = general_solutions(d, n)
+                    .take_while(|solution| solution.y <= BigUint::from(max_y))
+                    .collect();
+
+                assert_eq!(found, expected, "x² - {}y² = {}", d, n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_large_d() {
+        // x² - (k² + 4)y² = -4 has the solution (k, 1), and k² + 4 doesn't fit in an i64
+        let k = 4_000_000_000;
+        let first = general_solutions(k * k + 4, -4).next();
+
+        assert_eq!(first, Some(solution(k, 1)));
+    }
+
+    #[test]
+    fn test_general_solutions_with_large_units() {
+        // The fundamental solution for d = 61 is (1766319049, 226153980)
+        let first: Vec<_> = general_solutions(61, 3).take(2).collect();
+
+        for solution in first {
+            let (x, y) = (solution.x, solution.y);
+            assert_eq!(
+                &x * &x,
+                BigUint::from(61u32) * &y * &y + BigUint::from(3u32)
+            );
+        }
+    }
+}
@@ -5,6 +5,8 @@ where
     Self: Sized + AsMut<[A]> + AsRef<[A]>,
 {
     fn permutations(self) -> SEPA<Self, A>;
+    fn multiset_permutations(self) -> SEPA<Self, A>;
+    fn k_permutations(self, k: usize) -> KPermutations<Self, A>;
 }
 
 impl<A, T> Permutations<A> for T
@@ -19,6 +21,41 @@ where
             _marker: PhantomData,
         }
     }
+
+    // Repeated elements are fine: SEPA only ever moves to the next *distinct* arrangement, so all
+    // we need is to start from the lexicographically smallest one.
+    fn multiset_permutations(mut self) -> SEPA<T, A> {
+        self.as_mut().sort();
+        self.permutations()
+    }
+
+    fn k_permutations(mut self, k: usize) -> KPermutations<T, A> {
+        assert!(k <= self.as_ref().len());
+        self.as_mut().sort();
+
+        KPermutations {
+            current: self,
+            k,
+            first: true,
+            _marker: PhantomData,
+        }
+    }
+}
+
+// Visits each arrangement by reference, reusing the same buffer instead of allocating a new `Vec`
+// per item like the `Iterator` impls do.
+pub trait LendingPermutations<A> {
+    fn next_arrangement(&mut self) -> Option<&[A]>;
+
+    fn for_each_arrangement<F>(mut self, mut f: F)
+    where
+        Self: Sized,
+        F: FnMut(&[A]),
+    {
+        while let Some(arrangement) = self.next_arrangement() {
+            f(arrangement);
+        }
+    }
 }
 
 // http://permute.tchs.info/soda_submit.php
@@ -40,6 +77,12 @@ impl<A: Ord + Clone, T: AsMut<[A]> + AsRef<[A]>> Iterator for SEPA<T, A> {
     }
 }
 
+impl<A: Ord + Clone, T: AsMut<[A]> + AsRef<[A]>> LendingPermutations<A> for SEPA<T, A> {
+    fn next_arrangement(&mut self) -> Option<&[A]> {
+        self.permute().map(|slice| &*slice)
+    }
+}
+
 impl<A: Ord + Clone, T: AsMut<[A]> + AsRef<[A]>> SEPA<T, A> {
     pub fn permute<'a>(&'a mut self) -> Option<&'a mut [A]> {
         if self.first {
@@ -47,44 +90,97 @@ impl<A: Ord + Clone, T: AsMut<[A]> + AsRef<[A]>> SEPA<T, A> {
             return Some(self.current.as_mut());
         }
 
-        if let Some((key, newkey)) = self.next_keys() {
-            let slice = self.current.as_mut();
+        let slice = self.current.as_mut();
 
-            permute(slice, key, newkey);
+        if next_permutation(slice) {
             return Some(slice);
         }
 
         None
     }
+}
+
+pub struct KPermutations<T, A>
+where
+    T: AsMut<[A]> + AsRef<[A]>,
+{
+    current: T,
+    k: usize,
+    first: bool,
 
-    fn next_keys(&self) -> Option<(usize, usize)> {
-        let current_perm = self.current.as_ref();
-        let current_len = current_perm.len();
+    _marker: PhantomData<[A]>,
+}
 
-        let maybe_key_index: Option<usize> = (1..current_len)
-            .rev()
-            .find(|&index| {
-                let ref element = current_perm[index];
-                let ref element_before = current_perm[index - 1];
-
-                element > element_before
-            })
-            .map(|after_key_index| after_key_index - 1);
-
-        maybe_key_index.and_then(|key_index| {
-            let ref key_element = current_perm[key_index];
-
-            (key_index + 1..current_len)
-                .filter(|&index| {
-                    let ref element = current_perm[index];
-                    element > key_element
-                })
-                .min_by(|&index1, &index2| current_perm[index1].cmp(&current_perm[index2]))
-                .map(|newkey| (key_index, newkey))
-        })
+impl<A: Ord + Clone, T: AsMut<[A]> + AsRef<[A]>> Iterator for KPermutations<T, A> {
+    type Item = Vec<A>;
+
+    fn next(&mut self) -> Option<Vec<A>> {
+        self.next_arrangement().map(|slice| slice.to_vec())
     }
 }
 
+impl<A: Ord + Clone, T: AsMut<[A]> + AsRef<[A]>> LendingPermutations<A> for KPermutations<T, A> {
+    // The elements after `k` are always kept in ascending order. Reversing them makes the whole
+    // slice the last arrangement sharing its current k-prefix, so that the next full permutation
+    // is also the next k-permutation.
+    fn next_arrangement(&mut self) -> Option<&[A]> {
+        let k = self.k;
+        let slice = self.current.as_mut();
+
+        if self.first {
+            self.first = false;
+            return Some(&slice[..k]);
+        }
+
+        slice[k..].reverse();
+
+        if next_permutation(slice) {
+            return Some(&slice[..k]);
+        }
+
+        None
+    }
+}
+
+// Rearranges `slice` into the next lexicographically greater arrangement, returning false if it
+// was already the last one.
+fn next_permutation<A: Ord>(slice: &mut [A]) -> bool {
+    match next_keys(slice) {
+        Some((key, newkey)) => {
+            permute(slice, key, newkey);
+            true
+        }
+
+        None => false,
+    }
+}
+
+fn next_keys<A: Ord>(current_perm: &[A]) -> Option<(usize, usize)> {
+    let current_len = current_perm.len();
+
+    let maybe_key_index: Option<usize> = (1..current_len)
+        .rev()
+        .find(|&index| {
+            let element = &current_perm[index];
+            let element_before = &current_perm[index - 1];
+
+            element > element_before
+        })
+        .map(|after_key_index| after_key_index - 1);
+
+    // Everything after the key is in descending order, so the rightmost element bigger than the
+    // key is also the smallest one. Picking the rightmost among equal candidates keeps that order
+    // intact when there are repeated elements.
+    maybe_key_index.and_then(|key_index| {
+        let key_element = &current_perm[key_index];
+
+        (key_index + 1..current_len)
+            .rev()
+            .find(|&index| current_perm[index] > *key_element)
+            .map(|newkey| (key_index, newkey))
+    })
+}
+
 fn permute<T>(slice: &mut [T], key: usize, newkey: usize) {
     slice.swap(key, newkey);
 
@@ -97,3 +193,80 @@ fn permute<T>(slice: &mut [T], key: usize, newkey: usize) {
         from_start += 1;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{LendingPermutations, Permutations};
+
+    #[test]
+    fn test_full_permutations() {
+        let perms: Vec<_> = vec![1, 2, 3].permutations().collect();
+
+        assert_eq!(
+            perms,
+            vec![
+                vec![1, 2, 3],
+                vec![1, 3, 2],
+                vec![2, 1, 3],
+                vec![2, 3, 1],
+                vec![3, 1, 2],
+                vec![3, 2, 1],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_multiset_permutations() {
+        let perms: Vec<_> = vec![2, 1, 1].multiset_permutations().collect();
+        assert_eq!(perms, vec![vec![1, 1, 2], vec![1, 2, 1], vec![2, 1, 1]]);
+
+        let count = vec![1, 1, 2, 2, 3].multiset_permutations().count();
+        assert_eq!(count, 30);
+    }
+
+    #[test]
+    fn test_k_permutations() {
+        let perms: Vec<_> = vec![3, 1, 2].k_permutations(2).collect();
+
+        assert_eq!(
+            perms,
+            vec![
+                vec![1, 2],
+                vec![1, 3],
+                vec![2, 1],
+                vec![2, 3],
+                vec![3, 1],
+                vec![3, 2],
+            ]
+        );
+
+        assert_eq!(vec![0, 1, 2, 3, 4].k_permutations(3).count(), 60);
+        assert_eq!(vec![0, 1, 2].k_permutations(0).count(), 1);
+    }
+
+    #[test]
+    fn test_k_permutations_of_multiset() {
+        let perms: Vec<_> = vec![1, 1, 2].k_permutations(2).collect();
+        assert_eq!(perms, vec![vec![1, 1], vec![1, 2], vec![2, 1]]);
+    }
+
+    #[test]
+    fn test_lending_permutations() {
+        let mut seen = Vec::new();
+        [1, 2, 3, 4]
+            .k_permutations(2)
+            .for_each_arrangement(|arrangement| seen.push(arrangement[0] * 10 + arrangement[1]));
+
+        assert_eq!(seen, vec![12, 13, 14, 21, 23, 24, 31, 32, 34, 41, 42, 43]);
+
+        let mut perms = [1, 2, 3].permutations();
+        let mut count = 0;
+
+        while let Some(arrangement) = perms.next_arrangement() {
+            assert_eq!(arrangement.len(), 3);
+            count += 1;
+        }
+
+        assert_eq!(count, 6);
+    }
+}
@@ -47,20 +47,26 @@ where
 
 #[allow(missing_copy_implementations)]
 pub struct PrimeFactors {
-    remaining: u32,
-    divisor: u32,
+    remaining: u64,
+    divisor: u64,
 }
 
 impl Iterator for PrimeFactors {
-    type Item = u32;
+    type Item = u64;
 
-    fn next(&mut self) -> Option<u32> {
+    fn next(&mut self) -> Option<u64> {
         if self.remaining <= 1 {
             return None;
         }
 
         let mut new_divisor = self.divisor;
         while self.remaining % new_divisor > 0 {
+            // Nothing up to √remaining divides it, so it's prime
+            if new_divisor > self.remaining / new_divisor {
+                new_divisor = self.remaining;
+                break;
+            }
+
             new_divisor += 1;
         }
 
@@ -71,7 +77,7 @@ impl Iterator for PrimeFactors {
     }
 }
 
-pub fn prime_factors(n: u32) -> PrimeFactors {
+pub fn prime_factors(n: u64) -> PrimeFactors {
     return PrimeFactors {
         remaining: n,
         divisor: 2,
@@ -80,7 +86,7 @@ pub fn prime_factors(n: u32) -> PrimeFactors {
 
 pub type DistinctPrimeFactors = UniqueFilter<PrimeFactors>;
 
-pub fn distinct_prime_factors(n: u32) -> DistinctPrimeFactors {
+pub fn distinct_prime_factors(n: u64) -> DistinctPrimeFactors {
     UniqueFilter {
         last: None,
         iter: prime_factors(n),
@@ -88,14 +94,14 @@ pub fn distinct_prime_factors(n: u32) -> DistinctPrimeFactors {
 }
 
 pub struct UniqueFilter<T> {
-    last: Option<u32>,
+    last: Option<u64>,
     iter: T,
 }
 
-impl<T: Iterator<Item = u32>> Iterator for UniqueFilter<T> {
+impl<T: Iterator<Item = u64>> Iterator for UniqueFilter<T> {
     type Item = <T as Iterator>::Item;
 
-    fn next(&mut self) -> Option<u32> {
+    fn next(&mut self) -> Option<u64> {
         loop {
             let new_value = match self.iter.next() {
                 None => return None,
@@ -120,10 +126,10 @@ mod tests {
 
     macro_rules! prime_assert(
         ($func:ident, $num:expr, [ $($factor:expr),+ ]) => ({
-            let mut expected_factors: Vec<u32> = vec!( $($factor),* );
+            let mut expected_factors: Vec<u64> = vec!( $($factor),* );
             expected_factors.sort();
 
-            let mut returned = $func($num).collect::<Vec<u32>>();
+            let mut returned = $func($num).collect::<Vec<u64>>();
             returned.sort();
 
             assert_eq!(expected_factors, returned);
@@ -138,6 +144,9 @@ mod tests {
         prime_assert!(prime_factors, 644, [2, 2, 7, 23]);
         prime_assert!(prime_factors, 645, [3, 5, 43]);
         prime_assert!(prime_factors, 646, [2, 17, 19]);
+
+        prime_assert!(prime_factors, 1_000_000_007, [1_000_000_007]);
+        prime_assert!(prime_factors, 2 * 4_294_967_291, [2, 4_294_967_291]);
     }
 
     #[test]
@@ -1,53 +1,280 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+// A triplet (a, b, c) with a < b < c and a² + b² = c²
+pub type Triplet = (usize, usize, usize);
+
+// The legs are kept unsorted here, with `a` odd and `b` even, which is what the matrices expect.
+type Primitive = (i64, i64, i64);
+
+// The children of a primitive triplet in Berggren's tree, which contains every primitive triplet
+// exactly once, starting from (3, 4, 5). Children always have a bigger perimeter and hypotenuse.
+fn children((a, b, c): Primitive) -> [Primitive; 3] {
+    [
+        (a - 2 * b + 2 * c, 2 * a - b + 2 * c, 2 * a - 2 * b + 3 * c),
+        (a + 2 * b + 2 * c, 2 * a + b + 2 * c, 2 * a + 2 * b + 3 * c),
+        (
+            -a + 2 * b + 2 * c,
+            -2 * a + b + 2 * c,
+            -2 * a + 2 * b + 3 * c,
+        ),
+    ]
+}
+
+const ROOT: Primitive = (3, 4, 5);
+
+fn sorted((a, b, c): Primitive, multiple: usize) -> Triplet {
+    let (a, b) = if a < b { (a, b) } else { (b, a) };
+    (
+        a as usize * multiple,
+        b as usize * multiple,
+        c as usize * multiple,
+    )
+}
+
+// Every triplet, ordered by hypotenuse (and then by the shortest leg)
 pub struct Triplets {
-    counter: Counter,
+    // (hypotenuse, shortest leg, multiple, primitive)
+    queue: BinaryHeap<Reverse<(i64, i64, usize, Primitive)>>,
+    primitive_only: bool,
 }
 
 impl Iterator for Triplets {
-    type Item = (usize, usize, usize);
+    type Item = Triplet;
+
+    fn next(&mut self) -> Option<Triplet> {
+        let Reverse((_, _, multiple, primitive)) = self.queue.pop()?;
 
-    fn next(&mut self) -> Option<(usize, usize, usize)> {
-        self.counter.find(is_triplet)
+        if multiple == 1 {
+            for &child in children(primitive).iter() {
+                self.push(child, 1);
+            }
+        }
+
+        if !self.primitive_only {
+            self.push(primitive, multiple + 1);
+        }
+
+        Some(sorted(primitive, multiple))
     }
 }
 
-fn is_triplet(&(a, b, c): &(usize, usize, usize)) -> bool {
-    (c * c) == (a * a) + (b * b)
+impl Triplets {
+    fn starting_at_root(primitive_only: bool) -> Triplets {
+        let mut triplets = Triplets {
+            queue: BinaryHeap::new(),
+            primitive_only,
+        };
+
+        triplets.push(ROOT, 1);
+        triplets
+    }
+
+    fn push(&mut self, primitive: Primitive, multiple: usize) {
+        let (a, b, c) = primitive;
+        let key = (c * multiple as i64, a.min(b) * multiple as i64);
+
+        self.queue
+            .push(Reverse((key.0, key.1, multiple, primitive)));
+    }
 }
 
 pub fn new() -> Triplets {
-    let counter = Counter {
-        last_c: 1,
-        last_b: 0,
-        last_a: 0,
+    Triplets::starting_at_root(false)
+}
+
+// Only the triplets whose sides are coprime, ordered by hypotenuse
+pub fn primitive() -> Triplets {
+    Triplets::starting_at_root(true)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Limit {
+    Perimeter(usize),
+    Hypotenuse(usize),
+}
+
+impl Limit {
+    fn measure(self, (a, b, c): Primitive) -> usize {
+        match self {
+            Limit::Perimeter(_) => (a + b + c) as usize,
+            Limit::Hypotenuse(_) => c as usize,
+        }
+    }
+
+    fn max(self) -> usize {
+        match self {
+            Limit::Perimeter(max) | Limit::Hypotenuse(max) => max,
+        }
+    }
+}
+
+// The triplets within a limit, in no particular order. Walks Berggren's tree depth-first, cutting
+// off branches as soon as they go past the limit, so it never looks at a triplet out of bounds.
+pub struct Bounded {
+    limit: Limit,
+    stack: Vec<Primitive>,
+    current: Option<(Primitive, usize)>,
+    primitive_only: bool,
+}
+
+impl Iterator for Bounded {
+    type Item = Triplet;
+
+    fn next(&mut self) -> Option<Triplet> {
+        loop {
+            if let Some((primitive, multiple)) = self.current {
+                let fits = self.limit.measure(primitive) * multiple <= self.limit.max();
+
+                if fits && (multiple == 1 || !self.primitive_only) {
+                    self.current = Some((primitive, multiple + 1));
+                    return Some(sorted(primitive, multiple));
+                }
+            }
+
+            let primitive = self.stack.pop()?;
+
+            for &child in children(primitive).iter() {
+                if self.limit.measure(child) <= self.limit.max() {
+                    self.stack.push(child);
+                }
+            }
+
+            self.current = Some((primitive, 1));
+        }
+    }
+}
+
+fn bounded(limit: Limit, primitive_only: bool) -> Bounded {
+    let stack = if limit.measure(ROOT) <= limit.max() {
+        vec![ROOT]
+    } else {
+        vec![]
     };
-    Triplets { counter: counter }
+
+    Bounded {
+        limit,
+        stack,
+        current: None,
+        primitive_only,
+    }
 }
 
-struct Counter {
-    last_c: usize,
-    last_b: usize,
-    last_a: usize,
+pub fn up_to(limit: Limit) -> Bounded {
+    bounded(limit, false)
 }
 
-impl Iterator for Counter {
-    type Item = (usize, usize, usize);
+pub fn primitive_up_to(limit: Limit) -> Bounded {
+    bounded(limit, true)
+}
+
+// Every triplet that has `leg` as one of its legs, ordered by hypotenuse.
+//
+// From leg² = c² - x² = (c - x)(c + x), each way of splitting leg² into two factors d < e with the
+// same parity gives c = (e + d) / 2 and x = (e - d) / 2.
+pub fn with_leg(leg: usize) -> Vec<Triplet> {
+    let square = leg * leg;
+    let mut triplets = Vec::new();
 
-    fn next(&mut self) -> Option<(usize, usize, usize)> {
-        if self.last_a > 0 {
-            self.last_a -= 1;
-        } else if self.last_b > 1 {
-            let new_b = self.last_b - 1;
+    for d in (1..leg).rev() {
+        if !square.is_multiple_of(d) {
+            continue;
+        }
 
-            self.last_b = new_b;
-            self.last_a = new_b - 1;
-        } else {
-            let new_c = self.last_c + 1;
+        let e = square / d;
 
-            self.last_c = new_c;
-            self.last_b = new_c - 1;
-            self.last_a = new_c - 2;
+        if !(e - d).is_multiple_of(2) {
+            continue;
         }
 
-        Some((self.last_a, self.last_b, self.last_c))
+        let (c, other) = ((e + d) / 2, (e - d) / 2);
+        triplets.push((leg.min(other), leg.max(other), c));
+    }
+
+    triplets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{new, primitive, primitive_up_to, up_to, with_leg, Limit, Triplet};
+    use crate::roots::exact_sqrt;
+    use num::integer::gcd;
+
+    fn brute_force(max_hypotenuse: usize) -> Vec<Triplet> {
+        let mut triplets = Vec::new();
+
+        for c in 1..=max_hypotenuse {
+            for a in 1..c {
+                if let Some(b) = exact_sqrt((c * c - a * a) as u128) {
+                    if a < b as usize {
+                        triplets.push((a, b as usize, c));
+                    }
+                }
+            }
+        }
+
+        triplets
+    }
+
+    #[test]
+    fn test_ordered_triplets() {
+        let expected = brute_force(100);
+        let found: Vec<_> = new().take_while(|&(_, _, c)| c <= 100).collect();
+
+        assert_eq!(found, expected);
+
+        let primitives: Vec<_> = primitive().take(5).collect();
+        assert_eq!(
+            primitives,
+            vec![
+                (3, 4, 5),
+                (5, 12, 13),
+                (8, 15, 17),
+                (7, 24, 25),
+                (20, 21, 29)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bounded() {
+        let mut by_hypotenuse: Vec<_> = up_to(Limit::Hypotenuse(100)).collect();
+        by_hypotenuse.sort_by_key(|&(a, _, c)| (c, a));
+        assert_eq!(by_hypotenuse, brute_force(100));
+
+        let by_perimeter: Vec<_> = up_to(Limit::Perimeter(120)).collect();
+        let expected: Vec<_> = brute_force(120)
+            .into_iter()
+            .filter(|&(a, b, c)| a + b + c <= 120)
+            .collect();
+
+        assert_eq!(by_perimeter.len(), expected.len());
+        assert!(expected
+            .iter()
+            .all(|triplet| by_perimeter.contains(triplet)));
+
+        let primitives: Vec<_> = primitive_up_to(Limit::Hypotenuse(100)).collect();
+        assert_eq!(primitives.len(), 16);
+        assert!(primitives.iter().all(|&(a, b, c)| gcd(gcd(a, b), c) == 1));
+
+        assert_eq!(up_to(Limit::Perimeter(11)).count(), 0);
+    }
+
+    #[test]
+    fn test_with_leg() {
+        assert_eq!(
+            with_leg(12),
+            vec![(5, 12, 13), (9, 12, 15), (12, 16, 20), (12, 35, 37)]
+        );
+
+        // Leaves room for the biggest hypotenuse, (leg² + 1) / 2
+        for leg in 1..44 {
+            let expected: Vec<_> = brute_force(1000)
+                .into_iter()
+                .filter(|&(a, b, _)| a == leg || b == leg)
+                .collect();
+
+            assert_eq!(with_leg(leg), expected);
+        }
     }
 }
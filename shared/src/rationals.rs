@@ -0,0 +1,329 @@
+use num::rational::Ratio;
+use num::BigUint;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+
+type Fraction = (u128, u128);
+
+// Compares p/q to a/b
+fn compare((p, q): Fraction, (a, b): Fraction) -> Ordering {
+    (p * b).cmp(&(a * q))
+}
+
+// None if the numerator doesn't fit in a u64 (the denominator always does)
+fn to_ratio((p, q): Fraction) -> Option<Ratio<u64>> {
+    Some(Ratio::new(u64::try_from(p).ok()?, u64::try_from(q).ok()?))
+}
+
+// The closest fractions strictly below and strictly above `x` whose denominators are at most
+// `max_denominator`, i.e. x's neighbours in the Farey sequence of that order.
+//
+// Walks down the Stern-Brocot tree, keeping both bounds adjacent (pr·ql - pl·qr = 1). Runs of
+// steps in the same direction are taken at once, which is what makes this follow the continued
+// fraction of `x`, taking O(log max_denominator) iterations.
+fn neighbours(x: Ratio<u64>, max_denominator: u64) -> (Option<Fraction>, Fraction) {
+    assert!(max_denominator > 0);
+
+    let n = max_denominator as u128;
+    let target = (*x.numer() as u128, *x.denom() as u128);
+    let (a, b) = target;
+
+    if b == 1 {
+        let left = if a > 0 { Some((a * n - 1, n)) } else { None };
+        return (left, (a * n + 1, n));
+    }
+
+    let (mut pl, mut ql) = (a / b, 1);
+    let (mut pr, mut qr) = (a / b + 1, 1);
+
+    loop {
+        let mediant = (pl + pr, ql + qr);
+
+        if mediant.1 > n {
+            return (Some((pl, ql)), (pr, qr));
+        }
+
+        match compare(mediant, target) {
+            Ordering::Equal => {
+                // Between a bound and x, the closest fractions to x are those formed by
+                // repeatedly taking the mediant with x itself.
+                let (mp, mq) = mediant;
+                let left_steps = (n - ql) / mq;
+                let right_steps = (n - qr) / mq;
+
+                return (
+                    Some((pl + left_steps * mp, ql + left_steps * mq)),
+                    (pr + right_steps * mp, qr + right_steps * mq),
+                );
+            }
+
+            Ordering::Less => {
+                // Largest k where (pl + k·pr)/(ql + k·qr) < x
+                let steps = (a * ql - b * pl - 1) / (b * pr - a * qr);
+                let steps = steps.min((n - ql) / qr);
+
+                pl += steps * pr;
+                ql += steps * qr;
+            }
+
+            Ordering::Greater => {
+                // Largest k where (pr + k·pl)/(qr + k·ql) > x
+                let steps = (b * pr - a * qr - 1) / (a * ql - b * pl);
+                let steps = steps.min((n - qr) / ql);
+
+                pr += steps * pl;
+                qr += steps * ql;
+            }
+        }
+    }
+}
+
+// The biggest fraction smaller than `x` with denominator at most `max_denominator`. None if x is 0,
+// or if the neighbour's numerator doesn't fit in a u64, which can happen when x > 1 and its
+// denominator is at most `max_denominator`.
+pub fn left_neighbour(x: Ratio<u64>, max_denominator: u64) -> Option<Ratio<u64>> {
+    neighbours(x, max_denominator).0.and_then(to_ratio)
+}
+
+// The smallest fraction bigger than `x` with denominator at most `max_denominator`, or None if its
+// numerator doesn't fit in a u64.
+pub fn right_neighbour(x: Ratio<u64>, max_denominator: u64) -> Option<Ratio<u64>> {
+    to_ratio(neighbours(x, max_denominator).1)
+}
+
+// The fraction closest to `x` with denominator at most `max_denominator`. Ties go to the smallest
+// fraction.
+//
+// Unlike the neighbours, this always fits in a u64: x's denominator b is bigger than any candidate
+// denominator q, so the numerator is at most about x·q < x·b, which is x's own numerator.
+pub fn best_approximation(x: Ratio<u64>, max_denominator: u64) -> Ratio<u64> {
+    if *x.denom() <= max_denominator {
+        return x;
+    }
+
+    let closest = match neighbours(x, max_denominator) {
+        (None, right) => right,
+
+        (Some(left), right) => {
+            // x - l/m vs r/s - x, all over the common denominator b·m·s. Each product has three
+            // factors of up to 64 bits, so they don't fit in a u128.
+            let big = |n: u128| BigUint::from(n);
+            let (a, b) = (big(*x.numer() as u128), big(*x.denom() as u128));
+            let (l, m) = (big(left.0), big(left.1));
+            let (r, s) = (big(right.0), big(right.1));
+
+            let to_left = &a * &m * &s - &l * &b * &s;
+            let to_right = &r * &b * &m - &a * &m * &s;
+
+            if to_right < to_left {
+                right
+            } else {
+                left
+            }
+        }
+    };
+
+    to_ratio(closest).unwrap()
+}
+
+// The fractions between 0 and 1 with denominators up to n, in increasing order.
+pub struct Farey {
+    n: u128,
+    current: Option<Fraction>,
+    next: Fraction,
+}
+
+pub fn farey(n: u64) -> Farey {
+    assert!(n > 0);
+
+    Farey {
+        n: n as u128,
+        current: Some((0, 1)),
+        next: (1, n as u128),
+    }
+}
+
+// The terms of the Farey sequence of order n which are bigger than `x`.
+pub fn farey_after(n: u64, x: Ratio<u64>) -> Farey {
+    // Nothing in the sequence is bigger than 1
+    if x >= Ratio::from_integer(1) {
+        return Farey {
+            n: n as u128,
+            current: None,
+            next: (1, 1),
+        };
+    }
+
+    // x < 1, so its right neighbour is at most 1 and fits in a u64
+    let current = neighbours(x, n).1;
+    let next = neighbours(to_ratio(current).unwrap(), n).1;
+
+    Farey {
+        n: n as u128,
+        current: Some(current),
+        next,
+    }
+}
+
+impl Iterator for Farey {
+    type Item = Ratio<u64>;
+
+    // Given consecutive terms a/b and c/d, the next one is (kc - a)/(kd - b), with k = ⌊(n + b)/d⌋
+    fn next(&mut self) -> Option<Ratio<u64>> {
+        let (a, b) = self.current?;
+        let (c, d) = self.next;
+
+        if a == b {
+            self.current = None;
+        } else {
+            let k = (self.n + b) / d;
+
+            self.current = Some((c, d));
+            self.next = (k * c - a, k * d - b);
+        }
+
+        Some(Ratio::new_raw(a as u64, b as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{best_approximation, farey, farey_after, left_neighbour, right_neighbour};
+    use num::rational::Ratio;
+
+    fn r(numerator: u64, denominator: u64) -> Ratio<u64> {
+        Ratio::new(numerator, denominator)
+    }
+
+    #[test]
+    fn test_farey_sequence() {
+        let sequence: Vec<_> = farey(5).collect();
+
+        assert_eq!(
+            sequence,
+            vec![
+                r(0, 1),
+                r(1, 5),
+                r(1, 4),
+                r(1, 3),
+                r(2, 5),
+                r(1, 2),
+                r(3, 5),
+                r(2, 3),
+                r(3, 4),
+                r(4, 5),
+                r(1, 1),
+            ]
+        );
+
+        assert_eq!(farey(1).collect::<Vec<_>>(), vec![r(0, 1), r(1, 1)]);
+        assert_eq!(farey(8).count(), 23);
+    }
+
+    #[test]
+    fn test_farey_after() {
+        let between: Vec<_> = farey_after(8, r(1, 3))
+            .take_while(|&fraction| fraction < r(1, 2))
+            .collect();
+
+        assert_eq!(between, vec![r(3, 8), r(2, 5), r(3, 7)]);
+        assert_eq!(farey_after(5, r(1, 1)).count(), 0);
+        assert_eq!(
+            farey_after(5, r(7, 9)).collect::<Vec<_>>(),
+            vec![r(4, 5), r(1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_neighbours() {
+        assert_eq!(left_neighbour(r(3, 7), 8), Some(r(2, 5)));
+        assert_eq!(
+            left_neighbour(r(3, 7), 1_000_000),
+            Some(r(428_570, 999_997))
+        );
+        assert_eq!(left_neighbour(r(0, 1), 10), None);
+        assert_eq!(right_neighbour(r(0, 1), 10), Some(r(1, 10)));
+        assert_eq!(left_neighbour(r(2, 1), 3), Some(r(5, 3)));
+        assert_eq!(right_neighbour(r(7, 3), 2), Some(r(5, 2)));
+
+        for n in 1..15 {
+            let sequence: Vec<_> = farey(n).collect();
+
+            for denominator in 1..20 {
+                for numerator in 0..denominator {
+                    let x = r(numerator, denominator);
+
+                    let left = sequence.iter().rev().find(|&&f| f < x).cloned();
+                    let right = sequence.iter().find(|&&f| f > x).cloned();
+
+                    assert_eq!(left_neighbour(x, n), left, "{} in F{}", x, n);
+                    assert_eq!(right_neighbour(x, n), right, "{} in F{}", x, n);
+
+                    // Shifting x by an integer shifts its neighbours too
+                    let shifted = |f: Ratio<u64>| f + 3;
+                    assert_eq!(
+                        left_neighbour(shifted(x), n),
+                        Some(left.map_or(r(3 * n - 1, n), shifted)),
+                        "{} in F{}",
+                        shifted(x),
+                        n
+                    );
+                    assert_eq!(right_neighbour(shifted(x), n), right.map(shifted));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_large_neighbours() {
+        let n = 1 << 30;
+
+        // 2⁷⁰ + 1 doesn't fit in a u64
+        assert_eq!(right_neighbour(Ratio::from_integer(1 << 40), n), None);
+        assert_eq!(left_neighbour(Ratio::from_integer(1 << 40), n), None);
+        assert_eq!(
+            right_neighbour(Ratio::from_integer(1 << 20), n),
+            Some(r((1 << 50) + 1, n))
+        );
+        assert_eq!(
+            left_neighbour(Ratio::from_integer(1 << 20), n),
+            Some(r((1 << 50) - 1, n))
+        );
+
+        // Neighbours p/q of 5/2 have 2p - 5q = ±1, so q is the biggest odd number allowed
+        let q = (1 << 40) - 1;
+        assert_eq!(
+            right_neighbour(r(5, 2), 1 << 40),
+            Some(r((5 * q + 1) / 2, q))
+        );
+        assert_eq!(
+            left_neighbour(r(5, 2), 1 << 40),
+            Some(r((5 * q - 1) / 2, q))
+        );
+
+        assert_eq!(farey_after(n, r(3, 2)).count(), 0);
+    }
+
+    #[test]
+    fn test_best_approximation() {
+        let pi = r(3_141_592_653_589_793, 1_000_000_000_000_000);
+
+        assert_eq!(best_approximation(pi, 10), r(22, 7));
+        assert_eq!(best_approximation(pi, 1000), r(355, 113));
+        assert_eq!(best_approximation(r(1, 3), 5), r(1, 3));
+        assert_eq!(best_approximation(r(1, 4), 3), r(1, 3));
+        assert_eq!(best_approximation(r(1, 100), 3), r(0, 1));
+
+        // x = 2 - 2⁻⁶³ is halfway between (2⁶³ - 1)/2⁶² and 2
+        let x = r(u64::MAX, 1 << 63);
+        assert_eq!(best_approximation(x, 1 << 62), r((1 << 63) - 1, 1 << 62));
+        assert_eq!(
+            best_approximation(r(u64::MAX, (1 << 62) + 1), 1 << 62),
+            r(3_689_348_814_741_910_323, 922_337_203_685_477_581)
+        );
+        assert_eq!(
+            best_approximation(r(u64::MAX, 7), 3),
+            r(u64::MAX, 7).round()
+        );
+    }
+}
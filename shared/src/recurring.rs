@@ -0,0 +1,318 @@
+use crate::modular::{mul_mod, pow_mod};
+use crate::{primes, totient};
+use num::integer::gcd;
+use num::rational::Ratio;
+use num::{BigUint, ToPrimitive, Zero};
+use std::fmt;
+
+// The positional expansion of a non-negative fraction, split into its integer part, the digits
+// after the point that don't repeat, and the repetend (empty for terminating expansions).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Expansion {
+    pub base: u32,
+    pub integer_part: u64,
+    pub prefix: Vec<u8>,
+    pub repetend: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    InvalidDigit(char),
+    Malformed,
+    Overflow,
+}
+
+// Splits the (reduced) denominator into the part made of the base's prime factors, which gives the
+// length of the prefix, and the part coprime to the base, whose multiplicative order gives the
+// length of the repetend.
+fn lengths(denominator: u64, base: u64) -> (usize, usize) {
+    let mut coprime = denominator;
+    let mut common = 1;
+
+    loop {
+        let factor = gcd(coprime, base);
+
+        if factor == 1 {
+            break;
+        }
+
+        coprime /= factor;
+        common *= factor;
+    }
+
+    // Smallest k such that `common` divides baseᵏ
+    let mut prefix_len = 0;
+    let mut power = 1 % common;
+
+    while power != 0 {
+        power = mul_mod(power, base, common);
+        prefix_len += 1;
+    }
+
+    let repetend_len = if coprime == 1 {
+        0
+    } else {
+        multiplicative_order(base, coprime) as usize
+    };
+
+    (prefix_len, repetend_len)
+}
+
+pub fn expand(numerator: u64, denominator: u64, base: u32) -> Expansion {
+    assert!(denominator > 0);
+    assert!((2..=36).contains(&base));
+
+    let common = gcd(numerator, denominator).max(1);
+    let (numerator, denominator) = (numerator / common, denominator / common);
+
+    let (prefix_len, repetend_len) = lengths(denominator, base as u64);
+
+    let mut remainder = numerator % denominator;
+    let mut next_digit = || {
+        let shifted = remainder as u128 * base as u128;
+        remainder = (shifted % denominator as u128) as u64;
+
+        (shifted / denominator as u128) as u8
+    };
+
+    let prefix = (0..prefix_len).map(|_| next_digit()).collect();
+    let repetend = (0..repetend_len).map(|_| next_digit()).collect();
+
+    Expansion {
+        base,
+        integer_part: numerator / denominator,
+        prefix,
+        repetend,
+    }
+}
+
+// Length of the repetend of numerator/denominator in the given base, without computing any digits
+pub fn repetend_len(numerator: u64, denominator: u64, base: u32) -> usize {
+    let common = gcd(numerator, denominator).max(1);
+    lengths(denominator / common, base as u64).1
+}
+
+// Smallest k > 0 such that baseᵏ ≡ 1 (mod modulus), for a base coprime to the modulus.
+//
+// The order always divides φ(modulus), so we start from φ and remove prime factors for as long as
+// the result is still 1.
+pub fn multiplicative_order(base: u64, modulus: u64) -> u64 {
+    assert!(modulus > 1 && gcd(base, modulus) == 1);
+
+    let mut order = totient::of(modulus);
+
+    for prime in primes::distinct_prime_factors(order) {
+        while order.is_multiple_of(prime) && pow_mod(base, order / prime, modulus) == 1 {
+            order /= prime;
+        }
+    }
+
+    order
+}
+
+impl Expansion {
+    // The exact value, or None if its reduced form doesn't fit in a u64 fraction. Intermediate
+    // values are computed with big integers, so long repetends are fine.
+    pub fn to_ratio(&self) -> Option<Ratio<u64>> {
+        let base = BigUint::from(self.base);
+
+        let to_number = |digits: &[u8]| {
+            digits
+                .iter()
+                .fold(BigUint::zero(), |number, &digit| number * &base + digit)
+        };
+
+        let prefix = to_number(&self.prefix);
+        let prefix_scale = num::pow(base.clone(), self.prefix.len());
+
+        // 0.p(r) = (p·(bᴸ - 1) + r) / (bᵏ·(bᴸ - 1))
+        let (numerator, denominator) = if self.repetend.is_empty() {
+            (prefix, prefix_scale)
+        } else {
+            let nines = num::pow(base.clone(), self.repetend.len()) - 1u32;
+            let repetend = to_number(&self.repetend);
+
+            (prefix * &nines + repetend, prefix_scale * nines)
+        };
+
+        let value = Ratio::new(numerator, denominator) + BigUint::from(self.integer_part);
+
+        let (numerator, denominator) = (value.numer().to_u64()?, value.denom().to_u64()?);
+        Some(Ratio::new_raw(numerator, denominator))
+    }
+}
+
+// Renders as e.g. 0.1(6), or 0.25 for terminating expansions
+impl fmt::Display for Expansion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digit = |&digit: &u8| std::char::from_digit(digit as u32, self.base).unwrap();
+
+        write!(f, "{}", render_integer(self.integer_part, self.base))?;
+
+        if self.prefix.is_empty() && self.repetend.is_empty() {
+            return Ok(());
+        }
+
+        let prefix: String = self.prefix.iter().map(digit).collect();
+        write!(f, ".{}", prefix)?;
+
+        if !self.repetend.is_empty() {
+            let repetend: String = self.repetend.iter().map(digit).collect();
+            write!(f, "({})", repetend)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn render_integer(mut number: u64, base: u32) -> String {
+    let mut digits = Vec::new();
+
+    loop {
+        let digit = (number % base as u64) as u32;
+        digits.push(std::char::from_digit(digit, base).unwrap());
+        number /= base as u64;
+
+        if number == 0 {
+            break;
+        }
+    }
+
+    digits.into_iter().rev().collect()
+}
+
+// Parses strings in the format produced by `Display`, such as "0.1(6)", "12.5" or "3"
+pub fn parse(text: &str, base: u32) -> Result<Expansion, ParseError> {
+    assert!((2..=36).contains(&base));
+
+    let digits = |text: &str| -> Result<Vec<u8>, ParseError> {
+        text.chars()
+            .map(|chr| {
+                chr.to_digit(base)
+                    .map(|digit| digit as u8)
+                    .ok_or(ParseError::InvalidDigit(chr))
+            })
+            .collect()
+    };
+
+    let (integer, fraction) = match text.find('.') {
+        Some(index) => (&text[..index], Some(&text[index + 1..])),
+        None => (text, None),
+    };
+
+    if integer.is_empty() {
+        return Err(ParseError::Malformed);
+    }
+
+    let integer_part = digits(integer)?
+        .into_iter()
+        .try_fold(0u64, |number, digit| {
+            number.checked_mul(base as u64)?.checked_add(digit as u64)
+        })
+        .ok_or(ParseError::Overflow)?;
+
+    let (prefix, repetend) = match fraction {
+        None => (vec![], vec![]),
+
+        Some(fraction) => match fraction.find('(') {
+            None => (digits(fraction)?, vec![]),
+
+            Some(open) => {
+                if !fraction.ends_with(')') || open + 2 >= fraction.len() {
+                    return Err(ParseError::Malformed);
+                }
+
+                let repetend = &fraction[open + 1..fraction.len() - 1];
+                (digits(&fraction[..open])?, digits(repetend)?)
+            }
+        },
+    };
+
+    Ok(Expansion {
+        base,
+        integer_part,
+        prefix,
+        repetend,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand, multiplicative_order, parse, repetend_len, ParseError};
+    use num::rational::Ratio;
+
+    fn render(numerator: u64, denominator: u64, base: u32) -> String {
+        expand(numerator, denominator, base).to_string()
+    }
+
+    #[test]
+    fn test_unit_fractions() {
+        assert_eq!(render(1, 2, 10), "0.5");
+        assert_eq!(render(1, 3, 10), "0.(3)");
+        assert_eq!(render(1, 4, 10), "0.25");
+        assert_eq!(render(1, 6, 10), "0.1(6)");
+        assert_eq!(render(1, 7, 10), "0.(142857)");
+        assert_eq!(render(1, 9, 10), "0.(1)");
+        assert_eq!(render(1, 10, 10), "0.1");
+    }
+
+    #[test]
+    fn test_other_fractions() {
+        assert_eq!(render(22, 7, 10), "3.(142857)");
+        assert_eq!(render(10, 5, 10), "2");
+        assert_eq!(render(0, 5, 10), "0");
+        assert_eq!(render(1, 12, 10), "0.08(3)");
+        assert_eq!(render(1, 3, 2), "0.(01)");
+        assert_eq!(render(1, 3, 3), "0.1");
+        assert_eq!(render(255, 16, 16), "f.f");
+    }
+
+    #[test]
+    fn test_repetend_len() {
+        let longest = (2..1000).max_by_key(|&d| repetend_len(1, d, 10));
+        assert_eq!(longest, Some(983));
+
+        assert_eq!(repetend_len(1, 983, 10), 982);
+        assert_eq!(repetend_len(3, 6, 10), 0);
+        assert_eq!(multiplicative_order(10, 7), 6);
+        assert_eq!(multiplicative_order(2, 1_000_000_007), 500_000_003);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for base in &[2, 3, 10, 16] {
+            for denominator in 1..60 {
+                for numerator in 0..3 * denominator {
+                    let expansion = expand(numerator, denominator, *base);
+                    let parsed = parse(&expansion.to_string(), *base).unwrap();
+
+                    assert_eq!(parsed, expansion);
+                    assert_eq!(parsed.to_ratio(), Some(Ratio::new(numerator, denominator)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let parsed = parse("0.1(6)", 10).unwrap();
+        assert_eq!(parsed.to_ratio(), Some(Ratio::new(1, 6)));
+
+        // Non-canonical forms still evaluate correctly
+        let parsed = parse("0.(9)", 10).unwrap();
+        assert_eq!(parsed.to_ratio(), Some(Ratio::new(1, 1)));
+
+        assert_eq!(parse("0.1(", 10), Err(ParseError::Malformed));
+        assert_eq!(parse("0.()", 10), Err(ParseError::Malformed));
+        assert_eq!(parse(".5", 10), Err(ParseError::Malformed));
+        assert_eq!(parse("0.2", 2), Err(ParseError::InvalidDigit('2')));
+        assert_eq!(
+            parse("100000000000000000000", 10),
+            Err(ParseError::Overflow)
+        );
+
+        // Parses fine, but 10²¹ doesn't fit in the denominator
+        let parsed = parse("0.000000000000000000001", 10).unwrap();
+        assert_eq!(parsed.to_ratio(), None);
+    }
+}
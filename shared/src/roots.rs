@@ -0,0 +1,250 @@
+use num::bigint::BigUint;
+use num::rational::Ratio;
+use num::traits::{ToPrimitive, Zero};
+
+// floor(√n), exact for the whole u128 range
+pub fn isqrt(n: u128) -> u128 {
+    let mut root = (n as f64).sqrt() as u128;
+
+    while root.checked_mul(root).is_none_or(|square| square > n) {
+        root -= 1;
+    }
+
+    while (root + 1)
+        .checked_mul(root + 1)
+        .is_some_and(|square| square <= n)
+    {
+        root += 1;
+    }
+
+    root
+}
+
+pub fn exact_sqrt(n: u128) -> Option<u128> {
+    let root = isqrt(n);
+
+    if root * root == n {
+        Some(root)
+    } else {
+        None
+    }
+}
+
+// The k-th root of `x`, if it's rational
+pub fn exact_root(x: &Ratio<BigUint>, k: u32) -> Option<Ratio<BigUint>> {
+    assert!(k > 0);
+
+    let numerator = x.numer().nth_root(k);
+    let denominator = x.denom().nth_root(k);
+
+    if num::pow(numerator.clone(), k as usize) == *x.numer()
+        && num::pow(denominator.clone(), k as usize) == *x.denom()
+    {
+        Some(Ratio::new(numerator, denominator))
+    } else {
+        None
+    }
+}
+
+// The digits of the k-th root of a rational in some base, most significant first: those of the
+// integer part (a single 0 for roots below 1), followed by the ones after the point. Stops once
+// every remaining digit would be zero, which only happens for exact roots.
+//
+// Each fractional digit is found like in long-hand square roots: the biggest d such that
+// (b·r + d)ᵏ ≤ x·bᵏᵐ, where r is the root found so far and m the number of fractional digits.
+pub struct RootDigits {
+    k: u32,
+    base: BigUint,
+    numerator: BigUint,
+    denominator: BigUint,
+    scaled: BigUint,
+    root: BigUint,
+    integer_digits: Vec<u32>,
+}
+
+pub fn root_digits(x: Ratio<BigUint>, k: u32, base: u32) -> RootDigits {
+    assert!(k > 0 && base >= 2);
+
+    let root = (x.numer() / x.denom()).nth_root(k);
+    let mut integer_digits = to_digits(&root, base);
+    integer_digits.reverse();
+
+    RootDigits {
+        k,
+        base: BigUint::from(base),
+        numerator: x.numer().clone(),
+        denominator: x.denom().clone(),
+        scaled: x.numer().clone(),
+        root,
+        integer_digits,
+    }
+}
+
+// The decimal digits of √n
+pub fn sqrt_digits(n: u64) -> RootDigits {
+    root_digits(Ratio::from_integer(BigUint::from(n)), 2, 10)
+}
+
+fn to_digits(number: &BigUint, base: u32) -> Vec<u32> {
+    if number.is_zero() {
+        return vec![0];
+    }
+
+    let base = BigUint::from(base);
+    let mut number = number.clone();
+    let mut digits = Vec::new();
+
+    while !number.is_zero() {
+        digits.push((&number % &base).to_u32().unwrap());
+        number /= &base;
+    }
+
+    digits.reverse();
+    digits
+}
+
+impl RootDigits {
+    // Number of digits before the point
+    pub fn integer_len(&self) -> usize {
+        let integer_part = (&self.numerator / &self.denominator).nth_root(self.k);
+        to_digits(&integer_part, self.base.to_u32().unwrap()).len()
+    }
+
+    // The first `count` digits (counting from the start of the integer part) as a single number,
+    // found with one Newton iteration based root instead of one digit at a time. Digits past the
+    // end of an exact root count as zeros.
+    pub fn first_digits(&self, count: usize) -> BigUint {
+        let integer_len = self.integer_len();
+        let integer_part = (&self.numerator / &self.denominator).nth_root(self.k);
+
+        if count <= integer_len {
+            return integer_part / num::pow(self.base.clone(), integer_len - count);
+        }
+
+        let fractional_len = count - integer_len;
+        let scale = num::pow(self.base.clone(), fractional_len * self.k as usize);
+
+        (&self.numerator * scale / &self.denominator).nth_root(self.k)
+    }
+
+    fn is_exact(&self) -> bool {
+        num::pow(self.root.clone(), self.k as usize) * &self.denominator == self.scaled
+    }
+}
+
+impl Iterator for RootDigits {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if let Some(digit) = self.integer_digits.pop() {
+            return Some(digit);
+        }
+
+        if self.is_exact() {
+            return None;
+        }
+
+        self.scaled *= num::pow(self.base.clone(), self.k as usize);
+
+        let target = &self.scaled / &self.denominator;
+        let shifted = &self.root * &self.base;
+        let fits = |digit: &BigUint| num::pow(&shifted + digit, self.k as usize) <= target;
+
+        // Binary search for the biggest digit that keeps the root below the target
+        let (mut low, mut high) = (BigUint::zero(), self.base.clone());
+
+        while &low + 1u32 < high {
+            let middle: BigUint = (&low + &high) >> 1;
+
+            if fits(&middle) {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+
+        self.root = shifted + &low;
+        low.to_u32()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{exact_root, exact_sqrt, isqrt, root_digits, sqrt_digits};
+    use num::bigint::BigUint;
+    use num::rational::Ratio;
+
+    fn ratio(numerator: u32, denominator: u32) -> Ratio<BigUint> {
+        Ratio::new(BigUint::from(numerator), BigUint::from(denominator))
+    }
+
+    #[test]
+    fn test_isqrt() {
+        for n in 0..10_000 {
+            let root = isqrt(n);
+            assert!(root * root <= n && (root + 1) * (root + 1) > n);
+        }
+
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+        assert_eq!(isqrt((1 << 106) - 1), (1 << 53) - 1);
+        assert_eq!(isqrt(1 << 106), 1 << 53);
+    }
+
+    #[test]
+    fn test_exact_sqrt() {
+        assert_eq!(exact_sqrt(0), Some(0));
+        assert_eq!(exact_sqrt(49), Some(7));
+        assert_eq!(exact_sqrt(50), None);
+
+        // f32 rounds this to a perfect square
+        assert_eq!(exact_sqrt(16_777_217 * 16_777_217 + 1), None);
+    }
+
+    #[test]
+    fn test_exact_root() {
+        assert_eq!(exact_root(&ratio(4, 9), 2), Some(ratio(2, 3)));
+        assert_eq!(exact_root(&ratio(27, 8), 3), Some(ratio(3, 2)));
+        assert_eq!(exact_root(&ratio(2, 1), 2), None);
+        assert_eq!(exact_root(&ratio(4, 2), 2), None);
+    }
+
+    #[test]
+    fn test_sqrt_digits() {
+        let two: Vec<_> = sqrt_digits(2).take(21).collect();
+        assert_eq!(
+            two,
+            vec![1, 4, 1, 4, 2, 1, 3, 5, 6, 2, 3, 7, 3, 0, 9, 5, 0, 4, 8, 8, 0]
+        );
+
+        assert_eq!(sqrt_digits(2).take(100).sum::<u32>(), 475);
+        assert_eq!(sqrt_digits(100).collect::<Vec<_>>(), vec![1, 0]);
+        assert_eq!(sqrt_digits(0).collect::<Vec<_>>(), vec![0]);
+    }
+
+    #[test]
+    fn test_root_digits() {
+        let digits = |x, k, base| root_digits(x, k, base).take(8).collect::<Vec<_>>();
+
+        assert_eq!(digits(ratio(1, 4), 2, 10), vec![0, 5]);
+        assert_eq!(digits(ratio(1, 2), 2, 10), vec![0, 7, 0, 7, 1, 0, 6, 7]);
+        assert_eq!(digits(ratio(2, 1), 3, 10), vec![1, 2, 5, 9, 9, 2, 1, 0]);
+        assert_eq!(digits(ratio(2, 1), 2, 2), vec![1, 0, 1, 1, 0, 1, 0, 1]);
+        assert_eq!(digits(ratio(1000, 1), 3, 10), vec![1, 0]);
+        assert_eq!(root_digits(ratio(12345, 1), 2, 10).integer_len(), 3);
+    }
+
+    #[test]
+    fn test_first_digits() {
+        for n in 1..50 {
+            for &(k, base) in &[(2, 10), (3, 10), (2, 16), (5, 7)] {
+                let x = ratio(n, 7);
+                let expected = root_digits(x.clone(), k, base)
+                    .chain(std::iter::repeat(0))
+                    .take(30)
+                    .fold(BigUint::from(0u32), |number, digit| number * base + digit);
+
+                assert_eq!(root_digits(x, k, base).first_digits(30), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,20 @@
+// Deterministic pseudo-random numbers for tests, so we don't need `rand` just for fixtures
+pub struct Lcg {
+    state: u64,
+}
+
+impl Lcg {
+    pub fn new(seed: u64) -> Lcg {
+        Lcg { state: seed }
+    }
+
+    // A number in 0..bound, taken from the high bits, which are the most random ones
+    pub fn below(&mut self, bound: u32) -> u32 {
+        self.state = self
+            .state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1);
+
+        (self.state >> 33) as u32 % bound
+    }
+}
@@ -1,3 +1,4 @@
+use crate::primes;
 use crate::sieve::{self, Sieve};
 
 pub fn up_to(n: u64) -> Vec<u64> {
@@ -16,3 +17,8 @@ pub fn up_to(n: u64) -> Vec<u64> {
 
     phis.into_iter().skip(2).map(|phi| phi as u64).collect()
 }
+
+// φ(n) for a single n, from its distinct prime factors
+pub fn of(n: u64) -> u64 {
+    primes::distinct_prime_factors(n).fold(n, |phi, prime| phi / prime * (prime - 1))
+}
@@ -1,29 +1,192 @@
 use crate::a_star;
-use hashbrown::HashMap;
+use crate::data_reader;
+use std::io::Read;
+use std::ops::Add;
+use std::str::FromStr;
 
 #[derive(Eq, PartialEq, Debug, Hash, Copy, Clone)]
-pub struct Value {
+struct Value {
     coord: (u32, u32),
     value: u32,
 }
 
-pub struct Triangle {
-    values: HashMap<(u32, u32), Value>,
-    height: u32,
+// A path from top to bottom: its total and the (row, column) of each step
+pub type Path<T> = (T, Vec<(u32, u32)>);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Triangle<T = u32> {
+    rows: Vec<Vec<T>>,
+}
+
+pub fn new<T: Copy>(raw: &[&[T]]) -> Triangle<T> {
+    Triangle::from_rows(raw.iter().map(|row| row.to_vec()).collect())
+}
+
+// Parses one row per line, with the numbers separated by whitespace, as in data/67-triangle.txt
+impl<T: FromStr> FromStr for Triangle<T> {
+    type Err = data_reader::Error;
+
+    fn from_str(text: &str) -> Result<Triangle<T>, data_reader::Error> {
+        Ok(Triangle::from_rows(data_reader::triangle(text)?))
+    }
+}
+
+impl<T> Triangle<T> {
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Triangle<T> {
+        assert!(!rows.is_empty());
+
+        for (index, row) in rows.iter().enumerate() {
+            assert_eq!(row.len(), index + 1);
+        }
+
+        Triangle { rows }
+    }
+
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Triangle<T>, data_reader::Error>
+    where
+        T: FromStr,
+    {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+
+        text.parse()
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn get(&self, row: u32, col: u32) -> Option<&T> {
+        self.rows.get(row as usize)?.get(col as usize)
+    }
+}
+
+// One of the best paths from a node to the bottom: its total, and where it continues in the next
+// row, as the column and the index in that node's list of best paths
+#[derive(Clone)]
+struct Entry<T> {
+    total: T,
+    next: Option<(usize, usize)>,
+}
+
+impl<T: Copy + Ord + Add<Output = T>> Triangle<T> {
+    pub fn maximum_total(&self) -> T {
+        self.maximum_path().0
+    }
+
+    pub fn minimum_total(&self) -> T {
+        self.minimum_path().0
+    }
+
+    pub fn maximum_path(&self) -> Path<T> {
+        self.best_paths(1, |a, b| a > b).remove(0)
+    }
+
+    pub fn minimum_path(&self) -> Path<T> {
+        self.best_paths(1, |a, b| a < b).remove(0)
+    }
+
+    // The `k` paths with the biggest totals, best first
+    pub fn maximum_paths(&self, k: usize) -> Vec<Path<T>> {
+        self.best_paths(k, |a, b| a > b)
+    }
+
+    pub fn minimum_paths(&self, k: usize) -> Vec<Path<T>> {
+        self.best_paths(k, |a, b| a < b)
+    }
+
+    // Works from the bottom up, keeping the best `k` totals of the paths starting at each node.
+    // Those are found by merging the (already sorted) lists of the two nodes below it, so this
+    // takes O(cells·k). Ties go to the path that goes left first.
+    fn best_paths(&self, k: usize, better: impl Fn(&T, &T) -> bool) -> Vec<Path<T>> {
+        let height = self.rows.len();
+
+        if k == 0 {
+            return vec![];
+        }
+
+        let mut entries: Vec<Vec<Vec<Entry<T>>>> = vec![vec![]; height];
+
+        entries[height - 1] = self.rows[height - 1]
+            .iter()
+            .map(|&value| {
+                vec![Entry {
+                    total: value,
+                    next: None,
+                }]
+            })
+            .collect();
+
+        for row in (0..height - 1).rev() {
+            let below = &entries[row + 1];
+
+            let row_entries = self.rows[row]
+                .iter()
+                .enumerate()
+                .map(|(col, &value)| {
+                    let (left, right) = (&below[col], &below[col + 1]);
+                    let (mut i, mut j) = (0, 0);
+                    let mut merged = Vec::with_capacity(k);
+
+                    while merged.len() < k && (i < left.len() || j < right.len()) {
+                        let take_left = j >= right.len()
+                            || (i < left.len() && !better(&right[j].total, &left[i].total));
+
+                        let (total, next) = if take_left {
+                            i += 1;
+                            (left[i - 1].total, (col, i - 1))
+                        } else {
+                            j += 1;
+                            (right[j - 1].total, (col + 1, j - 1))
+                        };
+
+                        merged.push(Entry {
+                            total: value + total,
+                            next: Some(next),
+                        });
+                    }
+
+                    merged
+                })
+                .collect();
+
+            entries[row] = row_entries;
+        }
+
+        (0..entries[0][0].len())
+            .map(|index| {
+                let total = entries[0][0][index].total;
+                let mut path = vec![(0, 0)];
+                let mut next = entries[0][0][index].next;
+
+                while let Some((col, index)) = next {
+                    path.push((path.len() as u32, col as u32));
+                    next = entries[path.len() - 1][col][index].next;
+                }
+
+                (total, path)
+            })
+            .collect()
+    }
+}
+
+// A* minimizes the distance traveled, but here we want to *maximize* it.
+//
+// To trick A* into doing this we invert our node values, using the biggest node value as "0", so
+// that nodes with bigger values are seen as being "closer" by the algorithm.
+struct InvertedTriangle<'t> {
+    triangle: &'t Triangle<u32>,
     biggest_node_value: u32,
 }
 
-impl a_star::Traversable for Triangle {
+impl<'t> a_star::Traversable for InvertedTriangle<'t> {
     type Coord = Value;
+    type Cost = u32;
 
     fn heuristic(&self, start: Value) -> u32 {
-        self.height - start.coord.0
+        self.triangle.height() as u32 - start.coord.0
     }
 
-    // A* minimizes the distance traveled, but here we want to *maximize* it.
-    //
-    // To trick A* into doing this we invert our node values, using the biggest node value as "0",
-    // so that nodes with bigger values are seen as being "closer" by the algorithm.
     fn dist_between(&self, _start: Value, end: Value) -> u32 {
         self.biggest_node_value - end.value
     }
@@ -31,7 +194,7 @@ impl a_star::Traversable for Triangle {
     fn neighbors(&self, node: Value) -> Vec<Value> {
         let (row, col) = node.coord;
 
-        if row + 1 >= self.height {
+        if row as usize + 1 >= self.triangle.height() {
             return vec![];
         }
 
@@ -41,48 +204,157 @@ impl a_star::Traversable for Triangle {
         vec![left, right]
     }
 
-    fn reached_goal(&self, node: Self::Coord) -> bool {
-        node.coord.0 == self.height - 1
+    fn reached_goal(&self, node: Value) -> bool {
+        node.coord.0 as usize == self.triangle.height() - 1
     }
 }
 
-pub fn new(raw: &[&[u32]]) -> Triangle {
-    let mut values = HashMap::new();
-    let mut biggest_node_value = 0;
+impl<'t> InvertedTriangle<'t> {
+    fn get_coord(&self, coord: (u32, u32)) -> Value {
+        let value = *self.triangle.get(coord.0, coord.1).unwrap();
+        Value { coord, value }
+    }
+}
 
-    for (row_index, row) in raw.iter().enumerate() {
-        assert_eq!(row.len(), row_index + 1);
+impl Triangle<u32> {
+    // The original A* based solution, kept around to check the one above
+    pub fn maximum_total_a_star(&self) -> u32 {
+        let inverted = InvertedTriangle {
+            triangle: self,
+            biggest_node_value: self.rows.iter().flatten().cloned().max().unwrap(),
+        };
 
-        let row_index = row_index as u32;
+        // Every node but the ones in the last row leads to the next row, so there's always a path
+        let (_, path) = a_star::a_star(&inverted, inverted.get_coord((0, 0))).unwrap();
+        path.into_iter().map(|node| node.value).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{new, Triangle};
+    use crate::data_reader::{Error, Position};
+    use crate::test_random::Lcg;
+
+    const EXAMPLE: &[&[u32]] = &[&[3], &[7, 4], &[2, 4, 6], &[8, 5, 9, 3]];
 
-        for (index, &raw_value) in row.iter().enumerate() {
-            let coord = (row_index, index as u32);
-            let value = Value {
-                coord: coord,
-                value: raw_value,
-            };
+    fn generated(height: usize, seed: u64) -> Vec<Vec<u32>> {
+        let mut random = Lcg::new(seed);
+
+        (1..=height)
+            .map(|len| (0..len).map(|_| random.below(100)).collect())
+            .collect()
+    }
 
-            values.insert(coord, value);
-            if raw_value > biggest_node_value {
-                biggest_node_value = raw_value;
+    #[test]
+    fn test_example() {
+        let triangle = new(EXAMPLE);
+
+        assert_eq!(
+            triangle.maximum_path(),
+            (23, vec![(0, 0), (1, 0), (2, 1), (3, 2)])
+        );
+        assert_eq!(
+            triangle.minimum_path(),
+            (3 + 4 + 4 + 5, vec![(0, 0), (1, 1), (2, 1), (3, 1)])
+        );
+    }
+
+    #[test]
+    fn test_paths_match_totals() {
+        for seed in 0..20 {
+            let rows = generated(12, seed);
+            let triangle = Triangle::from_rows(rows.clone());
+
+            for (total, path) in [triangle.maximum_path(), triangle.minimum_path()] {
+                let sum: u32 = path
+                    .iter()
+                    .map(|&(row, col)| rows[row as usize][col as usize])
+                    .sum();
+
+                assert_eq!(sum, total);
             }
+
+            assert_eq!(triangle.maximum_total(), triangle.maximum_total_a_star());
         }
     }
 
-    Triangle {
-        height: raw.len() as u32,
-        values,
-        biggest_node_value,
+    #[test]
+    fn test_k_best_paths() {
+        let triangle = new(EXAMPLE);
+        let totals: Vec<u32> = triangle
+            .maximum_paths(5)
+            .into_iter()
+            .map(|(total, _)| total)
+            .collect();
+
+        assert_eq!(totals, vec![23, 22, 20, 20, 19]);
+        assert_eq!(triangle.maximum_paths(100).len(), 8);
+        assert_eq!(triangle.minimum_paths(2)[1].0, 16);
+
+        for seed in 0..10 {
+            let triangle = Triangle::from_rows(generated(8, seed));
+
+            // Brute force over every path, choosing left or right with each bit
+            let mut all: Vec<u32> = (0..1u32 << 7)
+                .map(|choices| {
+                    let mut col = 0;
+
+                    (0..8u32)
+                        .map(|row| {
+                            if row > 0 && choices & (1 << (row - 1)) != 0 {
+                                col += 1;
+                            }
+
+                            *triangle.get(row, col).unwrap()
+                        })
+                        .sum()
+                })
+                .collect();
+
+            all.sort_unstable_by(|a, b| b.cmp(a));
+
+            let best: Vec<u32> = triangle
+                .maximum_paths(10)
+                .into_iter()
+                .map(|(total, _)| total)
+                .collect();
+
+            assert_eq!(best, all[..10].to_vec());
+        }
     }
-}
 
-impl Triangle {
-    pub fn maximum_total(&self) -> u32 {
-        let path = a_star::a_star(self, self.get_coord((0, 0)));
-        path.into_iter().map(|node| node.value).sum()
+    #[test]
+    fn test_parse() {
+        let triangle: Triangle = "3\n7 4\n2 4 6\n8 5 9 3\n".parse().unwrap();
+        assert_eq!(triangle, new(EXAMPLE));
+
+        let triangle = Triangle::<u64>::from_reader("  1\n 2 3\n\n".as_bytes()).unwrap();
+        assert_eq!(triangle.maximum_total(), 4);
+
+        assert_eq!("".parse::<Triangle>(), Err(Error::Empty));
+        assert_eq!(
+            "1\n2 x".parse::<Triangle>(),
+            Err(Error::InvalidNumber {
+                position: Position { line: 2, column: 3 },
+                value: "x".to_owned()
+            })
+        );
+        assert_eq!(
+            "1\n2 3 4".parse::<Triangle>(),
+            Err(Error::WrongLength {
+                position: Position { line: 2, column: 1 },
+                expected: 2,
+                found: 3
+            })
+        );
     }
 
-    fn get_coord(&self, coord: (u32, u32)) -> Value {
-        self.values.get(&coord).cloned().unwrap()
+    #[test]
+    fn test_signed_values() {
+        let triangle = Triangle::from_rows(vec![vec![-1i64], vec![-5, 2], vec![10, -3, -4]]);
+
+        assert_eq!(triangle.maximum_path(), (4, vec![(0, 0), (1, 0), (2, 0)]));
+        assert_eq!(triangle.minimum_total(), -9);
     }
 }
@@ -2,11 +2,13 @@
  *
  * Find the greatest product of five consecutive digits in the 1000-digit number. */
 
-const NUMBER: &'static str = include_str!("../../data/08-number.txt");
+use shared::data;
+
 const DIGIT_COUNT: usize = 5;
 
 fn main() {
-    let result = find_biggest(consecutive_digits::new(NUMBER, DIGIT_COUNT));
+    let number = data::load_or_panic(8);
+    let result = find_biggest(consecutive_digits::new(&number, DIGIT_COUNT));
     println!("{}", result);
 }
 
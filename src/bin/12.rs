@@ -19,10 +19,12 @@
  *
  * What is the value of the first triangle number to have over five hundred divisors? */
 
+use shared::figurate;
+
 const NUMBER_OF_DIVISORS: u64 = 500;
 
 fn main() {
-    let result = triangular_numbers::new().find(|&num| {
+    let result = figurate::polygonal_numbers(3).find(|&num| {
         let divisor_count = (1..(num as f64).sqrt() as u64).fold(0, |sum, candidate| {
             if num % candidate == 0 {
                 sum + 2
@@ -36,28 +38,3 @@ fn main() {
 
     println!("{}", result.unwrap());
 }
-
-mod triangular_numbers {
-    pub struct TriangularNumbers {
-        last_number: u64,
-        sum: u64,
-    }
-
-    impl Iterator for TriangularNumbers {
-        type Item = u64;
-
-        fn next(&mut self) -> Option<u64> {
-            self.last_number += 1;
-            self.sum += self.last_number;
-
-            Some(self.sum)
-        }
-    }
-
-    pub fn new() -> TriangularNumbers {
-        TriangularNumbers {
-            last_number: 0,
-            sum: 0,
-        }
-    }
-}
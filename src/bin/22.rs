@@ -9,7 +9,7 @@
  *
  * What is the total of all the name scores in the file? */
 
-use shared::data_reader;
+use shared::{data, data_reader};
 use std::borrow::Borrow;
 
 fn main() {
@@ -34,5 +34,5 @@ fn alphabetical_value<S: Borrow<str>>(name: S) -> u32 {
 }
 
 fn get_name_list() -> Vec<String> {
-    data_reader::for_path("./data/22-names.txt").collect()
+    data_reader::words(&data::load_or_panic(22)).unwrap()
 }
@@ -17,56 +17,10 @@
  * Find the value of d < 1000 for which 1/d contains the longest recurring cycle in its decimal
  * fraction part. */
 
-use std::collections::HashMap;
+use shared::recurring;
 
 fn main() {
-    let mut seen: HashMap<u32, usize> = HashMap::new();
-
-    let result = (2u32..1_000).max_by_key(|&divisor| match division_type(1, divisor, &mut seen) {
-        DivisionType::Terminating => 0,
-        DivisionType::Recurring { size } => size,
-    });
+    let result = (2u64..1_000).max_by_key(|&divisor| recurring::repetend_len(1, divisor, 10));
 
     println!("{}", result.unwrap());
 }
-
-fn division_type(numerator: u32, denominator: u32, seen: &mut HashMap<u32, usize>) -> DivisionType {
-    seen.clear();
-    let mut remaining = numerator;
-
-    loop {
-        while remaining < denominator {
-            remaining *= 10;
-            let len = seen.len();
-            seen.insert(remaining, len);
-        }
-
-        let rest = remaining % denominator;
-        if rest == 0 {
-            return DivisionType::Terminating;
-        }
-
-        remaining = rest * 10;
-
-        let maybe_cycle_start = seen.get(&remaining).cloned();
-
-        let len = seen.len();
-        seen.insert(remaining, len);
-
-        match maybe_cycle_start {
-            Some(start) => {
-                return DivisionType::Recurring {
-                    size: (seen.len() - start - 1) as u16,
-                };
-            }
-
-            None => (),
-        }
-    }
-}
-
-#[derive(Debug)]
-enum DivisionType {
-    Terminating,
-    Recurring { size: u16 },
-}
@@ -11,24 +11,12 @@
  *
  * How many different ways can £2 be made using any number of coins? */
 
-use std::iter::repeat;
+use shared::partitions;
 
 const DENOMINATIONS: &'static [usize] = &[200, 100, 50, 20, 10, 5, 2, 1];
 
-fn main() {
-    println!("{}", ways_to_make(200, DENOMINATIONS));
-}
-
 // Dynamic programming solution, adapted from the overview PDF
-fn ways_to_make(value: usize, denominations: &[usize]) -> usize {
-    let mut ways: Vec<usize> = repeat(0).take(value + 1).collect();
-    ways[0] = 1;
-
-    for &denom in denominations.iter() {
-        for current in denom..value + 1 {
-            ways[current] += ways[current - denom];
-        }
-    }
-
-    *ways.last().unwrap()
+fn main() {
+    let ways: Vec<u64> = partitions::into_parts(200, DENOMINATIONS);
+    println!("{}", ways[200]);
 }
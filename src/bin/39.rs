@@ -7,45 +7,26 @@
  *
  * For which value of p ≤ 1000, is the number of solutions maximised? */
 
-#[cfg(not(test))]
-fn main() {
-    let result = (1..1_000)
-        .into_iter()
-        .max_by_key(|&perimeter| solution_count(perimeter))
-        .unwrap();
-
-    println!("{}", result);
-}
-
-fn solution_count(perimeter: usize) -> usize {
-    let mut count = 0;
-    let perimeter_by_3 = perimeter / 3;
-    let half_perimeter = perimeter / 2;
+use shared::geometry;
 
-    for a in 1..perimeter_by_3 {
-        let a_squared = a * a;
+const MAX_PERIMETER: usize = 1_000;
 
-        for b in half_perimeter - a..perimeter_by_3 + a {
-            let c = perimeter - (a + b);
-
-            let b_squared = b * b;
-            let c_squared = c * c;
+fn main() {
+    let counts = geometry::right_triangles_by_perimeter(MAX_PERIMETER);
 
-            if a_squared + b_squared == c_squared {
-                count += 1;
-            }
-        }
-    }
+    let result = (1..=MAX_PERIMETER)
+        .max_by_key(|&perimeter| counts[perimeter])
+        .unwrap();
 
-    return count;
+    println!("{}", result);
 }
 
 #[cfg(test)]
 mod tests {
-    use super::solution_count;
+    use shared::geometry;
 
     #[test]
     fn test_example_solution_count() {
-        assert_eq!(solution_count(120), 3);
+        assert_eq!(geometry::right_triangles_by_perimeter(120)[120], 3);
     }
 }
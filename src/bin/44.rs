@@ -11,14 +11,12 @@
  * Find the pair of pentagonal numbers, Pj and Pk, for which their sum and difference are pentagonal
  * and D = |Pk − Pj| is minimised; what is the value of D? */
 
-use num::ToPrimitive;
+use shared::figurate;
 
 fn main() {
-    let mut found: Vec<u32> = Vec::new();
-
-    for n in 1.. {
-        let pent1 = calculate_pentagonal_for(n);
+    let mut found: Vec<u64> = Vec::new();
 
+    for pent1 in figurate::polygonal_numbers(5) {
         for &pent2 in &found {
             if is_pentagonal(pent1 - pent2) && is_pentagonal(pent1 + pent2) {
                 println!("{}", pent1 - pent2);
@@ -30,29 +28,6 @@ fn main() {
     }
 }
 
-fn is_pentagonal(number: u32) -> bool {
-    let delta = 1 + 24 * number;
-
-    return take_sqrt(delta)
-        .and_then(to_integer)
-        .map(|integer_sqrt| (1 + integer_sqrt) % 6 == 0)
-        .unwrap_or(false);
-
-    fn take_sqrt(delta: u32) -> Option<f32> {
-        delta.to_f32().map(|as_float| as_float.sqrt())
-    }
-
-    fn to_integer(delta_sqrt: f32) -> Option<u32> {
-        let is_integer = delta_sqrt == delta_sqrt.floor();
-
-        if is_integer {
-            delta_sqrt.to_u32()
-        } else {
-            None
-        }
-    }
-}
-
-fn calculate_pentagonal_for(n: u32) -> u32 {
-    n * (3 * n - 1) / 2
+fn is_pentagonal(number: u64) -> bool {
+    figurate::is_polygonal(5, number).is_some()
 }
@@ -10,57 +10,14 @@
  *
  * Find the next triangle number that is also pentagonal and hexagonal. */
 
-const TARGET: u32 = 40755;
+use shared::figurate;
 
-fn main() {
-    let mut triangles = numbers_from_formula(|n| n * (n + 1) / 2);
-    let mut pentagonals = numbers_from_formula(|n| n * (3 * n - 1) / 2);
-    let hexagonals = numbers_from_formula(|n| n * (2 * n - 1));
-
-    for hex in hexagonals.skip_while(|&h| h <= TARGET) {
-        let pent = pentagonals.find(|&p| p >= hex).unwrap();
-        if pent != hex {
-            continue;
-        }
-
-        let trig = triangles.find(|&t| t >= hex).unwrap();
-        if trig != hex {
-            continue;
-        }
+const TARGET: u64 = 40755;
 
-        println!("{}", hex);
-        return;
-    }
-}
-
-fn numbers_from_formula<F>(formula: F) -> NumbersFromFormula<F>
-where
-    F: Fn(u32) -> u32,
-{
-    NumbersFromFormula {
-        last_n: 0,
-        formula: formula,
-    }
-}
-
-struct NumbersFromFormula<F>
-where
-    F: Fn(u32) -> u32,
-{
-    last_n: u32,
-    formula: F,
-}
-
-impl<F> Iterator for NumbersFromFormula<F>
-where
-    F: Fn(u32) -> u32,
-{
-    type Item = u32;
-
-    fn next(&mut self) -> Option<u32> {
-        let n = self.last_n + 1;
-        self.last_n = n;
+// Every hexagonal number is also a triangle number, so it's enough to check for pentagonals
+fn main() {
+    let hexagonals = figurate::polygonal_numbers(6);
+    let mut matches = hexagonals.filter(|&hex| figurate::is_polygonal(5, hex).is_some());
 
-        Some((self.formula)(n))
-    }
+    println!("{}", matches.find(|&hex| hex > TARGET).unwrap());
 }
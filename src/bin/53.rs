@@ -16,29 +16,11 @@
  * How many, not necessarily distinct, values of  nCr, for 1 ≤ n ≤ 100, are greater than
  * one-million? */
 
-const MAX_N: usize = 100;
-const MIN_VALUE: usize = 1_000_000;
+use shared::binomial;
 
-fn main() {
-    let count = (1..MAX_N + 1).fold(0, |prev, n| {
-        let mut value = 1usize;
-
-        // Multiplicative formula from:
-        // http://en.wikipedia.org/wiki/Binomial_coefficient#Multiplicative_formula
-        let first_k = (1..n / 2 + 1).find(|&k| {
-            let old_value = value;
-            value = old_value * (n + 1 - k) / k;
-
-            return value > MIN_VALUE;
-        });
+const MAX_N: u64 = 100;
+const MIN_VALUE: u64 = 1_000_000;
 
-        // Since the binomial is symmetrical, we can easily know how many results > 1_000_000 are there,
-        // given we know what the first one is.
-        match first_k {
-            Some(k) => prev + n + 1 - 2 * k,
-            None => prev,
-        }
-    });
-
-    println!("{}", count);
+fn main() {
+    println!("{}", binomial::count_exceeding(MAX_N, MIN_VALUE));
 }
@@ -43,6 +43,7 @@
 extern crate enum_primitive;
 
 use num::FromPrimitive;
+use shared::data_reader::{self, Suit};
 use std::cmp::Ordering;
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
@@ -78,27 +79,6 @@ enum CardValue {
 }
 }
 
-impl CardValue {
-    fn parse(value: &str) -> Option<CardValue> {
-        match value {
-            "2" => Some(CardValue::Two),
-            "3" => Some(CardValue::Three),
-            "4" => Some(CardValue::Four),
-            "5" => Some(CardValue::Five),
-            "6" => Some(CardValue::Six),
-            "7" => Some(CardValue::Seven),
-            "8" => Some(CardValue::Eight),
-            "9" => Some(CardValue::Nine),
-            "T" => Some(CardValue::Ten),
-            "J" => Some(CardValue::Jack),
-            "Q" => Some(CardValue::Queen),
-            "K" => Some(CardValue::King),
-            "A" => Some(CardValue::Ace),
-            _ => None,
-        }
-    }
-}
-
 enum_from_primitive! {
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 enum CardSuit {
@@ -109,36 +89,24 @@ enum CardSuit {
 }
 }
 
-impl CardSuit {
-    fn parse(suit: &str) -> Option<CardSuit> {
-        match suit {
-            "H" => Some(CardSuit::Hearts),
-            "S" => Some(CardSuit::Spades),
-            "C" => Some(CardSuit::Clubs),
-            "D" => Some(CardSuit::Diamonds),
-            _ => None,
-        }
-    }
-}
-
 #[derive(PartialEq, Eq, Clone, Copy)]
 struct Card {
     value: CardValue,
     suit: CardSuit,
 }
 
-impl Card {
-    fn parse(s: &str) -> Option<Card> {
-        let value = &s[..1];
-        let suit = &s[1..];
-
-        match (CardValue::parse(value), CardSuit::parse(suit)) {
-            (Some(card_value), Some(card_suit)) => Some(Card {
-                value: card_value,
-                suit: card_suit,
-            }),
+impl From<data_reader::Card> for Card {
+    fn from(card: data_reader::Card) -> Card {
+        let suit = match card.suit {
+            Suit::Spades => CardSuit::Spades,
+            Suit::Hearts => CardSuit::Hearts,
+            Suit::Diamonds => CardSuit::Diamonds,
+            Suit::Clubs => CardSuit::Clubs,
+        };
 
-            _ => None,
+        Card {
+            value: CardValue::from_u8(card.value - 2).unwrap(),
+            suit,
         }
     }
 }
@@ -182,20 +150,13 @@ impl Hand {
         }
     }
 
-    fn parse(s: &str) -> Option<Hand> {
-        let parse_cards = s.split(' ').map(Card::parse).collect::<Option<Vec<Card>>>();
-
-        return parse_cards.and_then(|mut cards| {
-            if cards.len() != 5 {
-                return None;
-            }
-
-            cards.sort();
+    fn new(cards: &[data_reader::Card]) -> Hand {
+        let mut cards: Vec<Card> = cards.iter().map(|&card| card.into()).collect();
+        cards.sort();
 
-            Some(Hand {
-                cards: [cards[0], cards[1], cards[2], cards[3], cards[4]],
-            })
-        });
+        Hand {
+            cards: [cards[0], cards[1], cards[2], cards[3], cards[4]],
+        }
     }
 }
 
@@ -315,20 +276,12 @@ impl Hand {
 
 #[cfg(not(test))]
 fn main() {
-    const HANDS: &'static str = include_str!("../../data/54-poker.txt");
-
-    let player_1_victories = HANDS
-        .lines()
-        .filter(|line| {
-            let maybe_hand1 = Hand::parse(&line[..14]);
-            let maybe_hand2 = Hand::parse(&line[15..]);
+    use shared::data;
 
-            match (maybe_hand1, maybe_hand2) {
-                (Some(ref hand1), Some(ref hand2)) => hand1.beats(hand2),
-
-                _ => panic!("Parsing problem on line:\n{}", line),
-            }
-        })
+    let player_1_victories = data_reader::card_hands(&data::load_or_panic(54), 10)
+        .unwrap()
+        .iter()
+        .filter(|cards| Hand::new(&cards[..5]).beats(&Hand::new(&cards[5..])))
         .count();
 
     println!("{}", player_1_victories);
@@ -337,9 +290,10 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::{Hand, Rank};
+    use shared::data_reader;
 
     fn hand(s: &str) -> Hand {
-        Hand::parse(s).unwrap()
+        Hand::new(&data_reader::card_hands(s, 5).unwrap()[0])
     }
 
     fn rank(s: &str) -> Rank {
@@ -24,16 +24,13 @@
  * in the original text. */
 
 use ascii::*;
+use shared::{data, data_reader};
 
-const CIPHER: &'static str = include_str!("../../data/59-cipher.txt");
 const KEY_LEN: usize = 3;
 const COMMON_WORDS: &'static [&'static str] = &["the", "be", "to", "of", "and"];
 
 fn main() {
-    let cipher: Vec<u8> = CIPHER
-        .split(',')
-        .map(|num| num.trim_end_matches('\n').parse().unwrap())
-        .collect();
+    let cipher = data_reader::cipher(&data::load_or_panic(59)).unwrap();
 
     let mut buffer = AsciiString::with_capacity(cipher.len());
     let mut key_gen = KeysGenerator::new();
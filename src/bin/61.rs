@@ -21,7 +21,7 @@
  * triangle, square, pentagonal, hexagonal, heptagonal, and octagonal, is represented by a different
  * number in the set. */
 
-use shared::digits;
+use shared::{digits, figurate};
 
 use enum_set::{CLike, EnumSet};
 use std::collections::BTreeMap;
@@ -174,14 +174,12 @@ impl Iterator for PolygonalIterator {
 }
 
 macro_rules! polygonal_formulas {
-    ( $($name:ident, $fname:ident: $p:pat => $f:expr),+ ) => {
+    ( $($name:ident, $fname:ident: $sides:expr),+ ) => {
         impl PolygonalIterator {
             $(
                 fn $fname() -> PolygonalIterator {
                     fn formula(index: u32) -> u32 {
-                        match index {
-                            $p => $f
-                        }
+                        figurate::polygonal($sides, index as u64) as u32
                     }
 
                     PolygonalIterator {
@@ -212,10 +210,10 @@ macro_rules! polygonal_formulas {
 }
 
 polygonal_formulas! {
-    Triangle,   triangle_numbers:    n => n * (n + 1) / 2,
-    Square,     square_numbers:      n => n * n,
-    Pentagonal, pentagonal_numbers:  n => n * (3*n - 1) / 2,
-    Hexagonal,  hexagonal_numbers:   n => n * (2*n - 1),
-    Heptagonal, heptagonal_numbers:  n => n * (5*n - 3) / 2,
-    Octagonal,  octagonal_numbers:   n => n * (3*n - 2)
+    Triangle,   triangle_numbers:   3,
+    Square,     square_numbers:     4,
+    Pentagonal, pentagonal_numbers: 5,
+    Hexagonal,  hexagonal_numbers:  6,
+    Heptagonal, heptagonal_numbers: 7,
+    Octagonal,  octagonal_numbers:  8
 }
@@ -27,104 +27,21 @@
  *
  * Find the sum of digits in the numerator of the 100th convergent of the continued fraction for e */
 
-use shared::digits;
-use std::cmp;
-use std::iter::repeat;
+use num::BigUint;
+use shared::continued_fraction;
 
-const INITIAL_TERM: u32 = 2;
 const CONVERGENT_INDEX: usize = 99;
 
 fn main() {
-    let (terms, last_term) = {
-        let mut iter = Term::initial();
+    let mut convergents = continued_fraction::e().convergents::<BigUint>();
+    let convergent = convergents.nth(CONVERGENT_INDEX).unwrap();
 
-        let terms: Vec<_> = [INITIAL_TERM]
-            .iter()
-            .map(|&n| n)
-            .chain(iter.by_ref())
-            .take(CONVERGENT_INDEX)
-            .collect();
+    let digit_sum: u32 = convergent
+        .numerator
+        .to_string()
+        .chars()
+        .map(|digit| digit.to_digit(10).unwrap())
+        .sum();
 
-        let last = iter.next().unwrap();
-
-        (terms, last)
-    };
-
-    let last_fraction: (Vec<u8>, Vec<u8>) = (digits::new(last_term).rev().collect(), vec![1]);
-
-    let rev_terms = terms.into_iter().rev();
-
-    // t + 1/(n/d) => t + d/n => (nt + d)/n
-    let (result, _) = rev_terms.fold(last_fraction, |(last_numerator, last_denominator), term| {
-        let new_numerator = calculate_numerator(term, &*last_numerator, &*last_denominator);
-        let new_denominator = last_numerator;
-
-        (new_numerator, new_denominator)
-    });
-
-    println!(
-        "{}",
-        result.into_iter().fold(0u16, |acc, n| acc + (n as u16))
-    );
-}
-
-fn calculate_numerator(term: u32, numerator: &[u8], denominator: &[u8]) -> Vec<u8> {
-    let numerator_iter = numerator.iter().map(|&n| n).chain(repeat(0));
-    let denominator_iter = denominator.iter().map(|&n| n).chain(repeat(0));
-
-    let sum_len = cmp::max(numerator.len(), denominator.len());
-    let mut final_number = Vec::with_capacity(sum_len);
-
-    let mut carry: u32 = 0;
-
-    for (num_digit, denom_digit) in numerator_iter.zip(denominator_iter).take(sum_len) {
-        let result: u32 = (num_digit as u32) * term + (denom_digit as u32) + carry;
-        carry = result / 10;
-
-        let digit: u8 = (result % 10) as u8;
-        final_number.push(digit);
-    }
-
-    if carry > 0 {
-        final_number.extend(digits::new::<_, u8>(carry).rev());
-    }
-
-    final_number
-}
-
-enum Term {
-    LeadingOne(u32),
-    Multiple(u32),
-    TrailingOne(u32),
-}
-
-impl Term {
-    fn initial() -> Term {
-        Term::LeadingOne(2)
-    }
-
-    fn value(&self) -> u32 {
-        match *self {
-            Term::LeadingOne(_) | Term::TrailingOne(_) => 1,
-            Term::Multiple(v) => v,
-        }
-    }
-}
-
-impl Iterator for Term {
-    type Item = u32;
-
-    fn next(&mut self) -> Option<u32> {
-        let new_term = match *self {
-            Term::LeadingOne(v) => Some(Term::Multiple(v)),
-            Term::Multiple(v) => Some(Term::TrailingOne(v)),
-            Term::TrailingOne(v) => v.checked_add(2).map(|n| Term::LeadingOne(n)),
-        };
-
-        new_term.map(|term| {
-            let value = self.value();
-            *self = term;
-            value
-        })
-    }
+    println!("{}", digit_sum);
 }
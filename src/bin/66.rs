@@ -21,47 +21,22 @@
  * Find the value of D ≤ 1000 in minimal solutions of x for which the largest value of x is
  * obtained. */
 
-use shared::continued_fraction::convergent_iterator;
-use std::collections::HashSet;
+use num::BigUint;
+use shared::pell;
 
-const MAX_D: u128 = 1000;
+const MAX_D: u64 = 1000;
 
 #[derive(Debug)]
 struct Solution {
-    d: u128,
-    x: u128,
+    d: u64,
+    x: BigUint,
 }
 
 fn main() {
-    let perfect_square_ds: HashSet<u128> = {
-        let max_d_root = (MAX_D as f64).sqrt().ceil() as u128;
+    let solution = (1..=MAX_D)
+        .filter_map(|d| pell::fundamental(d).map(|solution| Solution { d, x: solution.x }))
+        .max_by(|s1, s2| s1.x.cmp(&s2.x))
+        .unwrap();
 
-        (1..max_d_root).map(|root| root * root).collect()
-    };
-
-    let mut solution = Solution { d: 0, x: 0 };
-
-    for d in 1..MAX_D {
-        if perfect_square_ds.contains(&d) {
-            continue;
-        }
-
-        for pair in convergent_iterator(d.clone()) {
-            let x = pair.numerator;
-            let y = pair.denominator;
-
-            let numerator_squared = x.wrapping_mul(x);
-            let denominator_squared = y.wrapping_mul(y);
-
-            if denominator_squared.wrapping_mul(d) == numerator_squared - 1 {
-                if x > solution.x {
-                    solution = Solution { x, d };
-                }
-
-                break;
-            }
-        }
-    }
-
-    println!("{:?}", solution);
+    println!("{}", solution.d);
 }
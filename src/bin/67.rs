@@ -18,48 +18,13 @@
  * every second it would take over twenty billion years to check them all. There is an efficient
  * algorithm to solve it. ;o) */
 
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
-
-use shared::triangle;
+use shared::triangle::Triangle;
+use shared::{data, data_reader};
 
 fn main() {
-    let triangle = triangle::new(&deep_slice(&read_triangle()));
+    let rows = data_reader::triangle(&data::load_or_panic(67)).unwrap();
+    let triangle: Triangle = Triangle::from_rows(rows);
     let result = triangle.maximum_total();
 
     println!("{}", result);
 }
-
-fn read_triangle() -> Vec<Vec<u32>> {
-    let path = Path::new("./data/67-triangle.txt");
-    let file = BufReader::new(File::open(path).unwrap());
-
-    let mut result = Vec::new();
-
-    for line in file.lines() {
-        let line_text = line.unwrap();
-        let mut parsed_line = Vec::new();
-
-        for atom in line_text.trim().split(' ') {
-            match atom.parse::<u32>() {
-                Ok(num) => parsed_line.push(num),
-                Err(_) => continue,
-            }
-        }
-
-        result.push(parsed_line);
-    }
-
-    result
-}
-
-fn deep_slice(v: &Vec<Vec<u32>>) -> Vec<&[u32]> {
-    let mut result: Vec<&[u32]> = Vec::new();
-
-    for row in v.iter() {
-        result.push(&row);
-    }
-
-    result
-}
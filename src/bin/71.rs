@@ -13,38 +13,13 @@
  * find the numerator of the fraction immediately to the left of 3/7. */
 
 use num::rational::Ratio;
-use rayon::prelude::*;
+use shared::rationals;
 
-type Int = u64;
-const MAX: Int = 1_000_000;
+const MAX: u64 = 1_000_000;
 
 fn main() {
-    let target_ratio: Ratio<Int> = Ratio::new(3, 7);
+    let target_ratio: Ratio<u64> = Ratio::new(3, 7);
+    let result = rationals::left_neighbour(target_ratio, MAX).unwrap();
 
-    let result = (1..MAX)
-        .into_par_iter()
-        .flat_map(|denom| {
-            let midpoint = (target_ratio * denom).ceil().to_integer();
-            let start = midpoint.saturating_sub(1);
-
-            (start..midpoint)
-                .into_par_iter()
-                .map(move |num| (num, denom))
-        })
-        .filter_map(|(num, denom)| {
-            if denom == 0 || denom > MAX {
-                return None;
-            }
-
-            let ratio = Ratio::new(num, denom);
-
-            if ratio >= target_ratio {
-                None
-            } else {
-                Some(ratio)
-            }
-        })
-        .max();
-
-    println!("{:?}", result);
+    println!("{}", result.numer());
 }
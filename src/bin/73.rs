@@ -14,28 +14,14 @@
  * How many fractions lie between 1/3 and 1/2 in the sorted set of reduced proper fractions
  * for d ≤ 12,000? */
 
-use num::Integer;
-use rayon::prelude::*;
-
-use std::cmp;
+use num::rational::Ratio;
+use shared::rationals;
 
 const MAX: u64 = 12_000;
 
-// 1/3 < a/b < 1/2
-// 3 > b/a > 2
-// 3a > b > 2a
 fn main() {
-    let count = (1..MAX)
-        .into_par_iter()
-        .flat_map(|a| {
-            let min_b = (2 * a) + 1;
-            let max_b = cmp::min((3 * a) - 1, MAX);
-
-            let range = if min_b > MAX { 0..0 } else { min_b..max_b + 1 };
-
-            range.into_par_iter().map(move |b| (a, b))
-        })
-        .filter(|(a, b)| a.gcd(&b) == 1)
+    let count = rationals::farey_after(MAX, Ratio::new(1, 3))
+        .take_while(|&fraction| fraction < Ratio::new(1, 2))
         .count();
 
     println!("{}", count);
@@ -20,77 +20,13 @@
  * integer sided right angle triangle be formed?
  **/
 
-use num::Integer;
+use shared::geometry;
 
-use std::collections::HashMap;
-use std::iter;
-
-const MAX_LENGTH: i32 = 1_500_000;
+const MAX_LENGTH: usize = 1_500_000;
 
 fn main() {
-    let mut found_lengths = HashMap::new();
-    let triplets = triplets();
-
-    for triplet in triplets {
-        let sum = triplet.0 + triplet.1 + triplet.2;
+    let counts = geometry::right_triangles_by_perimeter(MAX_LENGTH);
+    let result = counts.iter().filter(|&&count| count == 1).count();
 
-        found_lengths
-            .entry(sum)
-            .and_modify(|c| *c = true)
-            .or_insert(false);
-    }
-
-    let result = found_lengths.values().filter(|&repeated| !repeated).count();
     println!("{}", result);
 }
-
-fn triplets() -> impl Iterator<Item = (i32, i32, i32)> {
-    coprime_pairs().flat_map(|(m, n)| triplets_from_pair(m, n))
-}
-
-fn triplets_from_pair(m: i32, n: i32) -> impl Iterator<Item = (i32, i32, i32)> {
-    let m2 = m * m;
-    let n2 = n * n;
-    let mut k = 1;
-
-    iter::from_fn(move || {
-        let a = k * (m2 - n2);
-        let b = k * 2 * m * n;
-        let c = k * (m2 + n2);
-
-        if a + b + c > MAX_LENGTH {
-            return None;
-        } else {
-            k += 1;
-        }
-
-        Some((a, b, c))
-    })
-}
-
-fn coprime_pairs() -> impl Iterator<Item = (i32, i32)> {
-    let mut m = 1;
-    let mut n = 0;
-
-    iter::from_fn(move || {
-        loop {
-            if n + 1 < m {
-                n += 1;
-            } else if (2 * m * m + 2 * m) < MAX_LENGTH - 1 {
-                m += 1;
-                n = 1;
-            } else {
-                return None;
-            }
-
-            let coprimes = m.gcd(&n) == 1;
-            let both_odd = (m % 2 == 1) && (n % 2 == 1);
-
-            if coprimes && !both_odd {
-                break;
-            }
-        }
-
-        Some((m, n))
-    })
-}
@@ -11,44 +11,14 @@
  * What is the first value which can be written as the sum of primes in over five thousand different ways?
  **/
 
-use shared::sieve;
+use shared::partitions;
 
-const COMPUTE_UNTIL: i32 = 1000;
-const TARGET: i32 = 5_000;
-
-#[derive(Debug)]
-struct WaysTracker {
-    number: i32,
-    ways: i32,
-}
-impl WaysTracker {
-    fn track(&mut self, number: i32, ways: i32) {
-        if ways < TARGET {
-            return;
-        }
-
-        if self.ways < TARGET || number < self.number {
-            *self = WaysTracker { number, ways };
-        }
-    }
-}
+const COMPUTE_UNTIL: usize = 1000;
+const TARGET: u64 = 5_000;
 
 fn main() {
-    let sieve = sieve::new::<i32>();
-    let primes = sieve.take_while(|&n| n <= COMPUTE_UNTIL);
-    let mut sums = vec![0; COMPUTE_UNTIL as usize];
-    sums[0] = 1;
-
-    let mut ways_tracker = WaysTracker { number: 0, ways: 1 };
-
-    for prime in primes {
-        let prime = prime as usize;
-
-        for index in prime..sums.len() {
-            sums[index] += sums[index - prime];
-            ways_tracker.track(index as i32, sums[index]);
-        }
-    }
+    let ways: Vec<u64> = partitions::into_primes(COMPUTE_UNTIL);
+    let first = ways.iter().position(|&ways| ways > TARGET);
 
-    println!("{:?}", ways_tracker);
+    println!("{}", first.unwrap());
 }
@@ -11,18 +11,14 @@
  **/
 
 use memchr::memchr;
+use shared::{data, data_reader, digits};
 use std::collections::HashSet;
 
-const KEYLOG: &'static str = include_str!("../../data/79-keylog.txt");
-
 fn main() {
-    let seen: HashSet<Vec<u8>> = KEYLOG
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|ch| ch.to_digit(10).unwrap() as u8)
-                .collect()
-        })
+    let seen: HashSet<Vec<u8>> = data_reader::numbers::<u32>(&data::load_or_panic(79))
+        .unwrap()
+        .into_iter()
+        .map(|attempt| digits::new(attempt).collect())
         .collect();
 
     let different_digits = seen
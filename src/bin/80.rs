@@ -11,71 +11,20 @@
  * hundred decimal digits for all the irrational square roots.
  **/
 
-use num::bigint::BigInt;
-use num::traits::Zero;
+use num::bigint::BigUint;
+use num::rational::Ratio;
 use rayon::prelude::*;
-use shared::digits;
+use shared::roots;
 
 fn main() {
-    let total: i32 = (1..=100)
+    let total: u32 = (1..=100u64)
         .into_par_iter()
-        .filter_map(|number| {
-            let (sum, digit_count) = square_digits(number)
-                .take(100)
-                .fold((0, 0), |(sum, digit_count), digit| {
-                    (sum + digit, digit_count + 1)
-                });
-
-            if digit_count == 100 {
-                Some(sum)
-            } else {
-                None
-            }
+        .filter(|&number| {
+            let number = Ratio::from_integer(BigUint::from(number));
+            roots::exact_root(&number, 2).is_none()
         })
+        .map(|number| roots::sqrt_digits(number).take(100).sum::<u32>())
         .sum();
 
     println!("{}", total);
 }
-
-fn square_digits(num: i32) -> impl Iterator<Item = i32> {
-    let mut num_digits = number_pairs(num);
-    let mut p = BigInt::zero();
-    let mut remainder = BigInt::zero();
-
-    std::iter::from_fn(move || {
-        let mut c: BigInt = &remainder * 100;
-        if let Some(digits) = num_digits.next() {
-            c += digits;
-        }
-
-        if c.is_zero() {
-            return None;
-        }
-
-        let x = (0..)
-            .take_while(|&x| (x * (20 * &p + x)) <= c)
-            .last()
-            .unwrap();
-
-        let y = x * (20 * &p + x);
-        remainder = c - y;
-        p = &p * 10 + x;
-
-        Some(x)
-    })
-}
-
-fn number_pairs(num: i32) -> impl Iterator<Item = u8> {
-    let mut digits: Vec<u8> = digits::new(num).rev().collect();
-
-    if digits.len() % 2 == 1 {
-        digits.push(0);
-    }
-
-    std::iter::from_fn(move || {
-        let d1 = digits.pop()?;
-        let d2 = digits.pop()?;
-
-        Some(d1 * 10 + d2)
-    })
-}
@@ -18,60 +18,37 @@
  * top left to the bottom right by only moving right and down.
  **/
 
-const MATRIX: &'static str = include_str!("../../data/81-matrix.txt");
-
-use shared::a_star::{Coord, Matrix};
+use shared::a_star::{Grid, Movement};
+use shared::data;
 
 fn main() {
-    let m = build_matrix();
-    let result = m.minimal_path_sum();
+    let (result, _) = build_matrix().minimal_monotone_path();
 
     println!("{}", result);
 }
 
-#[derive(Debug)]
-struct Problem81Matrix {
-    nodes: Vec<Vec<u32>>,
-}
-
-impl Matrix for Problem81Matrix {
-    fn nodes(&self) -> &[Vec<u32>] {
-        &self.nodes
-    }
-
-    fn neighbors(&self, node: Coord) -> Vec<Coord> {
-        let Coord { x, y } = node;
-
-        let right = Coord { x: x + 1, y };
-        let down = Coord { x, y: y + 1 };
-
-        let mut neighbors = vec![right, down];
-        neighbors.retain(|neighbor| self.contains(*neighbor));
-
-        neighbors
-    }
-}
-
-fn build_matrix() -> Problem81Matrix {
-    let nodes = MATRIX
-        .lines()
-        .map(|line| line.split(',').filter_map(|s| s.parse().ok()).collect())
-        .collect();
-
-    Problem81Matrix { nodes }
+fn build_matrix() -> Grid {
+    data::load_or_panic(81)
+        .parse::<Grid>()
+        .unwrap()
+        .with_movement(Movement::RightDown)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Problem81Matrix;
-    use shared::a_star::{self, Coord, Matrix};
+    use shared::a_star::{Coord, Grid, Matrix, Movement};
+
+    const DEMO: &str = "131,673,234,103,18
+201,96,342,965,150
+630,803,746,422,111
+537,699,497,121,956
+805,732,524,37,331";
 
     #[test]
     fn test_example_path() {
         let matrix = demo_matrix();
 
-        let start = matrix.top_left();
-        let path = a_star::a_star(&matrix, start);
+        let path = matrix.minimal_path().unwrap();
 
         assert_eq!(
             path,
@@ -95,18 +72,13 @@ mod tests {
 
         let result = matrix.minimal_path_sum();
 
-        assert_eq!(result, 2427)
+        assert_eq!(result, Ok(2427));
+        assert_eq!(matrix.minimal_monotone_path().0, 2427)
     }
 
-    fn demo_matrix() -> Problem81Matrix {
-        let nodes = vec![
-            vec![131, 673, 234, 103, 18],
-            vec![201, 96, 342, 965, 150],
-            vec![630, 803, 746, 422, 111],
-            vec![537, 699, 497, 121, 956],
-            vec![805, 732, 524, 37, 331],
-        ];
-
-        Problem81Matrix { nodes }
+    fn demo_matrix() -> Grid {
+        DEMO.parse::<Grid>()
+            .unwrap()
+            .with_movement(Movement::RightDown)
     }
 }
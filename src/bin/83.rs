@@ -19,68 +19,37 @@
  * top left to the bottom right by moving left, right, up, and down.
  **/
 
-const MATRIX: &'static str = include_str!("../../data/83-matrix.txt");
-
-use shared::a_star::{Coord, Matrix};
+use shared::a_star::{Grid, Matrix, Movement};
+use shared::data;
 
 fn main() {
-    let m = build_matrix();
-    let result = m.minimal_path_sum();
+    let result = build_matrix().minimal_path_sum().unwrap();
 
     println!("{}", result);
 }
 
-#[derive(Debug)]
-struct Problem83Matrix {
-    nodes: Vec<Vec<u32>>,
-}
-
-impl Matrix for Problem83Matrix {
-    fn nodes(&self) -> &[Vec<u32>] {
-        &self.nodes
-    }
-
-    fn neighbors(&self, node: Coord) -> Vec<Coord> {
-        let Coord { x, y } = node;
-
-        let up = Coord {
-            x,
-            y: y.wrapping_sub(1),
-        };
-        let right = Coord { x: x + 1, y };
-        let down = Coord { x, y: y + 1 };
-        let left = Coord {
-            x: x.wrapping_sub(1),
-            y,
-        };
-
-        let mut neighbors = vec![up, left, right, down];
-        neighbors.retain(|neighbor| self.contains(*neighbor));
-
-        neighbors
-    }
-}
-
-fn build_matrix() -> Problem83Matrix {
-    let nodes = MATRIX
-        .lines()
-        .map(|line| line.split(',').filter_map(|s| s.parse().ok()).collect())
-        .collect();
-
-    Problem83Matrix { nodes }
+fn build_matrix() -> Grid {
+    data::load_or_panic(83)
+        .parse::<Grid>()
+        .unwrap()
+        .with_movement(Movement::FourWay)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Problem83Matrix;
-    use shared::a_star::{self, Coord, Matrix};
+    use shared::a_star::{Coord, Grid, Matrix, Movement};
+
+    const DEMO: &str = "131,673,234,103,18
+201,96,342,965,150
+630,803,746,422,111
+537,699,497,121,956
+805,732,524,37,331";
 
     #[test]
     fn test_example_path() {
         let matrix = demo_matrix();
 
-        let start = matrix.top_left();
-        let path = a_star::a_star(&matrix, start);
+        let path = matrix.minimal_path().unwrap();
 
         assert_eq!(
             path,
@@ -108,18 +77,12 @@ mod tests {
 
         let result = matrix.minimal_path_sum();
 
-        assert_eq!(result, 2297)
+        assert_eq!(result, Ok(2297))
     }
 
-    fn demo_matrix() -> Problem83Matrix {
-        let nodes = vec![
-            vec![131, 673, 234, 103, 18],
-            vec![201, 96, 342, 965, 150],
-            vec![630, 803, 746, 422, 111],
-            vec![537, 699, 497, 121, 956],
-            vec![805, 732, 524, 37, 331],
-        ];
-
-        Problem83Matrix { nodes }
+    fn demo_matrix() -> Grid {
+        DEMO.parse::<Grid>()
+            .unwrap()
+            .with_movement(Movement::FourWay)
     }
 }
@@ -15,54 +15,14 @@
  * Find the least value of M such that the number of solutions first exceeds one million.
  **/
 
-const TARGET: u32 = 1_000_000;
-const BASE_M: u32 = 100;
+use shared::geometry;
 
-fn main() {
-    let mut count = int_solutions_until(BASE_M);
-
-    for m in BASE_M.. {
-        count += int_solutions(m);
-
-        if count >= TARGET {
-            println!("{}", m);
-            break;
-        }
-    }
-}
-
-fn int_solutions_until(m: u32) -> u32 {
-    let mut count = 0;
-
-    for a in 1..=m {
-        count += int_solutions(a);
-    }
-
-    count
-}
+const TARGET: u64 = 1_000_000;
 
-fn int_solutions(a: u32) -> u32 {
-    let mut count = 0;
-
-    for b in 1..=a {
-        for c in 1..=b {
-            let path = shortest_path(a as f32, b as f32, c as f32);
-            if is_integer(path) {
-                count += 1;
-            }
-        }
-    }
-
-    count
-}
-
-fn shortest_path(a: f32, b: f32, c: f32) -> f32 {
-    // d² = a² + (b + c)²
-    let d2 = (a * a) + (b * b) + (c * c) + (2.0 * b * c);
-
-    d2.sqrt()
-}
+fn main() {
+    let (result, _) = geometry::cuboid_routes()
+        .find(|&(_, count)| count > TARGET)
+        .unwrap();
 
-fn is_integer(n: f32) -> bool {
-    n - n.trunc() == 0.0
+    println!("{}", result);
 }